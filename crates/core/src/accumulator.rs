@@ -0,0 +1,74 @@
+/// Buffers rows in memory and flushes them in batches.
+///
+/// Source crates wrap this with a type-specific `push` (e.g.
+/// `ArticleAccumulator::push`) that converts a parsed record into columns
+/// before appending. Flushing is row-count based by default; callers with
+/// rows of wildly varying size can pair it with a byte budget via
+/// [`Accumulator::with_byte_budget`] and [`Accumulator::push_sized`].
+pub struct Accumulator<T> {
+    rows: Vec<T>,
+    batch_rows: usize,
+    byte_budget: Option<usize>,
+    bytes: usize,
+}
+
+impl<T> Accumulator<T> {
+    pub fn new(batch_rows: usize) -> Self {
+        Accumulator {
+            rows: Vec::with_capacity(batch_rows),
+            batch_rows,
+            byte_budget: None,
+            bytes: 0,
+        }
+    }
+
+    /// Like [`Accumulator::new`], but also flushes once bytes pushed via
+    /// [`Accumulator::push_sized`] reach `byte_budget`, whichever trips first.
+    pub fn with_byte_budget(batch_rows: usize, byte_budget: usize) -> Self {
+        Accumulator {
+            byte_budget: Some(byte_budget),
+            ..Accumulator::new(batch_rows)
+        }
+    }
+
+    /// Appends a row, returning `true` if the caller should flush now.
+    pub fn push(&mut self, row: T) -> bool {
+        self.push_sized(row, 0)
+    }
+
+    /// Appends a row of a known size, returning `true` if the caller should
+    /// flush now because either the row count or the byte budget was hit.
+    pub fn push_sized(&mut self, row: T, size_bytes: usize) -> bool {
+        self.rows.push(row);
+        self.bytes += size_bytes;
+        self.rows.len() >= self.batch_rows || self.byte_budget.is_some_and(|budget| self.bytes >= budget)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Drains all buffered rows for the caller to write out.
+    pub fn take(&mut self) -> Vec<T> {
+        self.bytes = 0;
+        std::mem::take(&mut self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_budget_flushes_before_row_count() {
+        let mut acc: Accumulator<String> = Accumulator::with_byte_budget(100, 10);
+
+        assert!(!acc.push_sized("a".repeat(4), 4));
+        assert!(acc.push_sized("b".repeat(8), 8));
+        assert_eq!(acc.len(), 2);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::error::{Error, Result};
+
+/// Parquet compression codec, threaded from configs into [`crate::sink::ParquetSink`].
+///
+/// `zstd_level: i32` fields on source-crate configs predate this enum and
+/// keep working as a compatibility shim that maps straight to `Zstd`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Zstd(i32),
+    Snappy,
+    Uncompressed,
+    Gzip(u32),
+}
+
+impl Compression {
+    pub(crate) fn to_parquet(self) -> Result<parquet::basic::Compression> {
+        Ok(match self {
+            Compression::Zstd(level) => parquet::basic::Compression::ZSTD(
+                parquet::basic::ZstdLevel::try_new(level).map_err(|e| Error::Other(e.to_string()))?,
+            ),
+            Compression::Snappy => parquet::basic::Compression::SNAPPY,
+            Compression::Uncompressed => parquet::basic::Compression::UNCOMPRESSED,
+            Compression::Gzip(level) => parquet::basic::Compression::GZIP(
+                parquet::basic::GzipLevel::try_new(level).map_err(|e| Error::Other(e.to_string()))?,
+            ),
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use std::io;
+
+/// Error type shared by every stage of the pipeline.
+///
+/// Source-specific crates (`papeline-pubmed`, `papeline-openalex`, ...) wrap
+/// their own parse errors but bottom out here for anything that crosses a
+/// stage boundary (IO, HTTP, serialization).
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("http error: {0}")]
+    Http(#[from] Box<ureq::Error>),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[error("arrow error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[error("{0}")]
+    Other(String),
+
+    #[error("failed after {attempts} attempts: {source}")]
+    RetryExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        Error::Http(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
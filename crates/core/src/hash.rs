@@ -0,0 +1,110 @@
+use std::io::{self, Write};
+
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+
+use crate::error::Result;
+
+/// Hashes a single byte slice with BLAKE3, returning the hex digest used
+/// throughout the pipeline for content-addressable naming.
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex().to_string()
+}
+
+/// Combines several independently-computed hex digests into one, order
+/// sensitive. Used to derive a stage's content hash from its inputs'
+/// content hashes without re-reading the underlying bytes.
+pub fn combine_hashes<'a>(hashes: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for h in hashes {
+        hasher.update(h.as_bytes());
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// A `std::io::Write` adapter around `blake3::Hasher`, so a hash can be fed
+/// from anything that writes bytes (e.g. an Arrow IPC writer) without
+/// materializing them first.
+pub struct Hasher(blake3::Hasher);
+
+impl Hasher {
+    pub fn new() -> Self {
+        Hasher(blake3::Hasher::new())
+    }
+
+    pub fn finalize(&self) -> blake3::Hash {
+        self.0.finalize()
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Hasher::new()
+    }
+}
+
+impl Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Hashes a `RecordBatch`'s logical content by streaming it through Arrow
+/// IPC into a [`Hasher`], rather than hashing an already-encoded parquet
+/// file. Two files with the same schema and rows but different compression
+/// or row-group layout produce the same hash here, unlike [`hash_bytes`]
+/// over their raw file contents.
+pub fn hash_record_batch(batch: &RecordBatch) -> Result<blake3::Hash> {
+    let mut hasher = Hasher::new();
+    {
+        let mut writer = StreamWriter::try_new(&mut hasher, &batch.schema())?;
+        writer.write(batch)?;
+        writer.finish()?;
+    }
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::sync::Arc;
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::compression::Compression;
+    use crate::sink::ParquetSink;
+
+    fn write_and_read_back(compression: Compression) -> RecordBatch {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1, 2, 3]))]).unwrap();
+
+        let mut sink = ParquetSink::new(&path, schema, compression).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.finalize().unwrap();
+
+        let file = File::open(&path).unwrap();
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap();
+        reader.next().unwrap().unwrap()
+    }
+
+    #[test]
+    fn same_logical_content_hashes_equal_across_compression_codecs() {
+        let zstd_batch = write_and_read_back(Compression::Zstd(15));
+        let snappy_batch = write_and_read_back(Compression::Snappy);
+
+        assert_eq!(
+            hash_record_batch(&zstd_batch).unwrap(),
+            hash_record_batch(&snappy_batch).unwrap()
+        );
+    }
+}
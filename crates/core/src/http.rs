@@ -0,0 +1,227 @@
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// Knobs shared by every HTTP fetch in the pipeline (PubMed FTP-over-HTTP
+/// mirrors, OpenAlex/S2 APIs, S3-compatible object stores).
+///
+/// `connect_timeout` and `read_timeout` are tracked separately so a
+/// slow-to-connect host and a mid-stream stall surface as distinct failures
+/// instead of both tripping one conflated `timeout`.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Max time to establish the TCP/TLS connection.
+    pub connect_timeout: Duration,
+    /// Max time to receive the response body, passed to `ureq` as
+    /// `timeout_recv_body`. It bounds the whole body download; the gap
+    /// between individual reads is not timed separately.
+    pub read_timeout: Duration,
+    pub user_agent: String,
+    pub max_retries: u32,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            user_agent: "papeline/0.1".to_string(),
+            max_retries: 5,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Starts building an [`HttpConfig`] from [`HttpConfig::default`],
+    /// validating the result on [`HttpConfigBuilder::build`] so a caller
+    /// wiring up flags one at a time can't silently end up with a
+    /// zero-timeout or absurd retry count.
+    pub fn builder() -> HttpConfigBuilder {
+        HttpConfigBuilder::default()
+    }
+}
+
+/// Max `max_retries` [`HttpConfigBuilder::build`] accepts; higher than this
+/// is almost always a misplaced flag (e.g. a byte count) rather than an
+/// intentional retry budget.
+const MAX_REASONABLE_RETRIES: u32 = 20;
+
+/// Builder for [`HttpConfig`]. Unset fields keep the default's value;
+/// [`HttpConfigBuilder::build`] rejects zero timeouts and unreasonable
+/// retry counts instead of letting them through to `ureq`.
+#[derive(Debug, Clone)]
+pub struct HttpConfigBuilder {
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    user_agent: String,
+    max_retries: u32,
+}
+
+impl Default for HttpConfigBuilder {
+    fn default() -> Self {
+        let defaults = HttpConfig::default();
+        HttpConfigBuilder {
+            connect_timeout: defaults.connect_timeout,
+            read_timeout: defaults.read_timeout,
+            user_agent: defaults.user_agent,
+            max_retries: defaults.max_retries,
+        }
+    }
+}
+
+impl HttpConfigBuilder {
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Validates and assembles the [`HttpConfig`]: both timeouts must be
+    /// nonzero (a zero timeout means "never wait," not "no timeout," and
+    /// fails every request instantly) and `max_retries` must be at most
+    /// [`MAX_REASONABLE_RETRIES`].
+    pub fn build(self) -> Result<HttpConfig> {
+        if self.connect_timeout.is_zero() {
+            return Err(Error::Other("connect_timeout must be greater than zero".to_string()));
+        }
+        if self.read_timeout.is_zero() {
+            return Err(Error::Other("read_timeout must be greater than zero".to_string()));
+        }
+        if self.max_retries > MAX_REASONABLE_RETRIES {
+            return Err(Error::Other(format!(
+                "max_retries {} exceeds the reasonable limit of {MAX_REASONABLE_RETRIES}",
+                self.max_retries
+            )));
+        }
+        Ok(HttpConfig {
+            connect_timeout: self.connect_timeout,
+            read_timeout: self.read_timeout,
+            user_agent: self.user_agent,
+            max_retries: self.max_retries,
+        })
+    }
+}
+
+// Process-wide default, set once at startup from CLI flags so every source
+// crate picks up the same timeouts without threading a config object through
+// every fetch call.
+static GLOBAL_HTTP_CONFIG: OnceLock<Mutex<HttpConfig>> = OnceLock::new();
+
+fn global_config() -> &'static Mutex<HttpConfig> {
+    GLOBAL_HTTP_CONFIG.get_or_init(|| Mutex::new(HttpConfig::default()))
+}
+
+/// Overrides the process-wide [`HttpConfig`].
+pub fn set_http_config(config: HttpConfig) {
+    *global_config().lock().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// Returns the current process-wide [`HttpConfig`], or the default if it
+/// hasn't been set.
+pub fn http_config() -> HttpConfig {
+    global_config().lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Restores the process-wide [`HttpConfig`] to [`HttpConfig::default`].
+pub fn reset_http_config() {
+    set_http_config(HttpConfig::default());
+}
+
+// Serializes tests that install a scoped override, since the config itself
+// is process-wide: without this, two tests overriding it concurrently could
+// each restore over the other's config instead of their own prior value.
+static CONFIG_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// A scoped override of the process-wide [`HttpConfig`], installed by
+/// [`HttpConfigGuard::install`] and reverted back to whatever was active
+/// before when the guard drops. Also holds a lock serializing other
+/// guard-based tests, since the config is global.
+pub struct HttpConfigGuard {
+    previous: HttpConfig,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl HttpConfigGuard {
+    pub fn install(config: HttpConfig) -> Self {
+        let lock = CONFIG_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = http_config();
+        set_http_config(config);
+        HttpConfigGuard { previous, _lock: lock }
+    }
+}
+
+impl Drop for HttpConfigGuard {
+    fn drop(&mut self) {
+        set_http_config(self.previous.clone());
+    }
+}
+
+/// Builds a `ureq` agent honoring the given config's connect and read
+/// timeouts.
+pub fn build_agent(config: &HttpConfig) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_connect(Some(config.connect_timeout))
+        .timeout_recv_body(Some(config.read_timeout))
+        .user_agent(config.user_agent.clone())
+        .build()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_guard_reverts_the_config_once_dropped() {
+        reset_http_config();
+        let before = http_config();
+
+        {
+            let overridden = HttpConfig {
+                user_agent: "test-agent/1.0".to_string(),
+                ..HttpConfig::default()
+            };
+            let _guard = HttpConfigGuard::install(overridden);
+            assert_eq!(http_config().user_agent, "test-agent/1.0");
+        }
+
+        assert_eq!(http_config().user_agent, before.user_agent);
+    }
+
+    #[test]
+    fn builder_produces_the_configured_values_when_valid() {
+        let config = HttpConfig::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(20))
+            .max_retries(3)
+            .user_agent("papeline-test/1.0")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.connect_timeout, Duration::from_secs(5));
+        assert_eq!(config.read_timeout, Duration::from_secs(20));
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.user_agent, "papeline-test/1.0");
+    }
+
+    #[test]
+    fn builder_rejects_a_zero_read_timeout() {
+        let result = HttpConfig::builder().read_timeout(Duration::ZERO).build();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! Backend-agnostic line-JSON parsing for hot loops (S2/OpenAlex
+//! line-delimited records are the dominant CPU cost on full runs), so a
+//! source crate can swap `serde_json` for `simd-json` behind the
+//! `simd-json` feature without touching call sites.
+
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+
+/// Parses one line of newline-delimited JSON into `T`.
+///
+/// Takes `line` by `&mut str` even on the `serde_json` backend so the
+/// signature doesn't change across features: the `simd-json` backend parses
+/// in place over the line's own bytes (avoiding a second allocation per
+/// line), which mutates it destructively. Callers should treat `line` as
+/// consumed after this returns, regardless of which backend is active.
+#[cfg(not(feature = "simd-json"))]
+pub fn from_line<T: DeserializeOwned>(line: &mut str) -> Result<T> {
+    serde_json::from_str(line).map_err(Error::from)
+}
+
+/// Parses one line of newline-delimited JSON into `T` via `simd-json`,
+/// which parses in place over `line`'s bytes instead of allocating a fresh
+/// copy the way `serde_json::from_str` does.
+#[cfg(feature = "simd-json")]
+pub fn from_line<T: DeserializeOwned>(line: &mut str) -> Result<T> {
+    // SAFETY: simd-json only rewrites escape sequences in place and never
+    // grows the buffer, so the byte slice stays within `line`'s allocation
+    // and valid for the duration of the call. `line` is documented as
+    // consumed after this returns, so we don't rely on it staying valid
+    // UTF-8 afterward.
+    let bytes = unsafe { line.as_bytes_mut() };
+    simd_json::from_slice(bytes).map_err(|e| Error::Other(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct S2Paper {
+        #[serde(rename = "paperId")]
+        paper_id: String,
+        title: String,
+        year: Option<u32>,
+    }
+
+    #[test]
+    fn parses_a_representative_s2_paper_line() {
+        let mut line = r#"{"paperId":"abc123","title":"On Papers","year":2021}"#.to_string();
+        let paper: S2Paper = from_line(&mut line).unwrap();
+        assert_eq!(
+            paper,
+            S2Paper { paper_id: "abc123".to_string(), title: "On Papers".to_string(), year: Some(2021) }
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Shared primitives used by every `papeline` source crate: error types,
+//! HTTP configuration, retry/backoff, content hashing, parquet sinks,
+//! progress bars, the worker queue, and the shared temp directory.
+
+pub mod accumulator;
+pub mod compression;
+pub mod error;
+pub mod hash;
+pub mod http;
+pub mod json;
+pub mod progress;
+pub mod retry;
+pub mod sink;
+pub mod temp;
+#[cfg(feature = "testutil")]
+pub mod testutil;
+pub mod work_queue;
+
+pub use compression::Compression;
+pub use error::{Error, Result};
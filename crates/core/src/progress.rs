@@ -0,0 +1,296 @@
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle, TermLike};
+
+/// How a [`ProgressContext`] renders its bars.
+///
+/// `Auto` leaves TTY detection to indicatif's default draw target, which
+/// over some SSH/tmux setups detects a terminal but still renders poorly,
+/// flooding the scrollback. `Plain` sidesteps that entirely: no cursor
+/// movement, just a throttled summary line written every interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressMode {
+    #[default]
+    Auto,
+    Bars,
+    Plain,
+    None,
+}
+
+/// How often [`ProgressMode::Plain`] writes a summary line.
+const PLAIN_SUMMARY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Owns the `MultiProgress` shared by every bar a `papeline` command draws,
+/// so stages stack instead of clobbering each other's line.
+pub struct ProgressContext {
+    multi: MultiProgress,
+    mode: ProgressMode,
+}
+
+impl ProgressContext {
+    pub fn new() -> Self {
+        ProgressContext::with_mode(ProgressMode::Auto)
+    }
+
+    /// Like [`ProgressContext::new`], but renders according to `mode`
+    /// instead of indicatif's own TTY detection.
+    pub fn with_mode(mode: ProgressMode) -> Self {
+        ProgressContext::with_mode_and_interval(mode, PLAIN_SUMMARY_INTERVAL)
+    }
+
+    fn with_mode_and_interval(mode: ProgressMode, plain_interval: Duration) -> Self {
+        let multi = match mode {
+            ProgressMode::Auto | ProgressMode::Bars => MultiProgress::new(),
+            ProgressMode::Plain => MultiProgress::with_draw_target(ProgressDrawTarget::term_like(
+                Box::new(PlainSummaryTarget::new(plain_interval)),
+            )),
+            ProgressMode::None => MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+        };
+        ProgressContext { multi, mode }
+    }
+
+    /// Adds a new determinate bar with `total` units of work.
+    pub fn bar(&self, total: u64, message: impl Into<String>) -> ProgressBar {
+        let bar = ProgressBar::new(total);
+        bar.set_style(
+            ProgressStyle::with_template("{msg} {bar:40.cyan/blue} {pos}/{len} ({eta})")
+                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+        );
+        bar.set_message(message.into());
+        self.multi.add(bar)
+    }
+
+    /// Adds a bar tracking overall progress across `total_stages` stages
+    /// (e.g. N fetches plus the join they gate), so a multi-stage command
+    /// can show one combined elapsed/ETA line instead of leaving the
+    /// reader to add up per-stage bars themselves. The caller advances it
+    /// with `.inc(1)` as each stage finishes; a join gated on fetches
+    /// simply isn't advanced until every fetch it depends on has ticked.
+    ///
+    /// TTY-only: in [`ProgressMode::Plain`] and [`ProgressMode::None`] this
+    /// returns a hidden bar, since an aggregate ETA line adds noise without
+    /// a terminal to redraw it in. `Plain` still prints the throttled
+    /// summary of the [`ProgressContext::bar`] bars; `None` draws nothing.
+    pub fn overall_bar(&self, total_stages: u64) -> ProgressBar {
+        match self.mode {
+            ProgressMode::Auto | ProgressMode::Bars => {
+                let bar = ProgressBar::new(total_stages);
+                bar.set_style(
+                    ProgressStyle::with_template("overall {bar:40.green/blue} {pos}/{len} stages ({eta})")
+                        .unwrap_or_else(|_| ProgressStyle::default_bar()),
+                );
+                self.multi.add(bar)
+            }
+            ProgressMode::Plain | ProgressMode::None => ProgressBar::hidden(),
+        }
+    }
+}
+
+impl Default for ProgressContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tracks a `(timestamp, cumulative count)` sample pair so a shard loop can
+/// report throughput (MB/s, rows/s, ...) instead of just a raw total. Each
+/// [`RollingRate::sample`] call computes the rate since the previous sample;
+/// the first call has nothing to compare against and returns `0.0`.
+#[derive(Debug, Clone, Copy)]
+pub struct RollingRate {
+    last: Option<(Instant, u64)>,
+}
+
+impl RollingRate {
+    pub fn new() -> Self {
+        RollingRate { last: None }
+    }
+
+    /// Records `cumulative` (e.g. total bytes or rows seen so far) at `now`
+    /// and returns the per-second rate since the last sample.
+    pub fn sample(&mut self, now: Instant, cumulative: u64) -> f64 {
+        let rate = match self.last {
+            Some((last_time, last_count)) => {
+                let elapsed = now.duration_since(last_time).as_secs_f64();
+                if elapsed <= 0.0 {
+                    0.0
+                } else {
+                    (cumulative.saturating_sub(last_count)) as f64 / elapsed
+                }
+            }
+            None => 0.0,
+        };
+        self.last = Some((now, cumulative));
+        rate
+    }
+}
+
+impl Default for RollingRate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A [`TermLike`] that drops cursor-movement entirely and throttles
+/// `write_line` to at most one line per `interval`, so redraws degrade to a
+/// periodic summary instead of flooding the scrollback.
+struct PlainSummaryTarget {
+    interval: Duration,
+    last_written: Mutex<Option<Instant>>,
+    writer: Box<dyn Fn(&str) + Send + Sync>,
+}
+
+impl PlainSummaryTarget {
+    fn new(interval: Duration) -> Self {
+        PlainSummaryTarget::with_writer(interval, |line| eprintln!("{line}"))
+    }
+
+    fn with_writer(interval: Duration, writer: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        PlainSummaryTarget {
+            interval,
+            last_written: Mutex::new(None),
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Forwards `line` to the writer unless one was already written within
+    /// the throttle interval.
+    fn emit(&self, line: &str) {
+        let mut last_written = self.last_written.lock().unwrap();
+        let now = Instant::now();
+        if last_written.is_some_and(|t| now.duration_since(t) < self.interval) {
+            return;
+        }
+        *last_written = Some(now);
+        (self.writer)(line);
+    }
+}
+
+impl fmt::Debug for PlainSummaryTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlainSummaryTarget").field("interval", &self.interval).finish()
+    }
+}
+
+impl TermLike for PlainSummaryTarget {
+    fn width(&self) -> u16 {
+        80
+    }
+
+    fn move_cursor_up(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_down(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_right(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_left(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_line(&self, s: &str) -> io::Result<()> {
+        self.emit(s);
+        Ok(())
+    }
+
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        self.emit(s);
+        Ok(())
+    }
+
+    fn clear_line(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn plain_target_throttles_and_never_moves_the_cursor() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        let target = PlainSummaryTarget::with_writer(Duration::from_millis(50), move |line| {
+            sink.lock().unwrap().push(line.to_string());
+        });
+
+        target.write_line("12/40 shards, 3.2GB, 45k papers").unwrap();
+        target.write_line("13/40 shards, 3.3GB, 46k papers").unwrap();
+        assert_eq!(lines.lock().unwrap().len(), 1, "second write within the interval should be dropped");
+
+        std::thread::sleep(Duration::from_millis(60));
+        target.write_line("20/40 shards, 4.0GB, 60k papers").unwrap();
+        assert_eq!(lines.lock().unwrap().len(), 2);
+
+        assert!(target.move_cursor_up(5).is_ok());
+        assert!(target.clear_line().is_ok());
+        for line in lines.lock().unwrap().iter() {
+            assert!(!line.contains('\u{1b}'), "plain summary lines must never carry ANSI escape codes");
+        }
+    }
+
+    #[test]
+    fn overall_bar_reaches_completion_after_gated_fetches_and_join() {
+        let ctx = ProgressContext::with_mode(ProgressMode::Bars);
+        let overall = ctx.overall_bar(4); // 3 fetches + 1 join
+
+        let fetches = ["fetch_a", "fetch_b", "fetch_c"];
+        for _ in fetches {
+            // each fetch completing ticks the overall bar independently
+            overall.inc(1);
+        }
+        assert_eq!(overall.position(), 3, "join must not advance until every fetch it depends on has");
+
+        // the join only ticks once all fetches it's gated on have finished
+        overall.inc(1);
+        assert!(overall.is_finished() || overall.position() == overall.length().unwrap());
+    }
+
+    #[test]
+    fn rolling_rate_computes_bytes_per_second_between_samples() {
+        let mut rate = RollingRate::new();
+        let start = Instant::now();
+
+        assert_eq!(rate.sample(start, 0), 0.0, "first sample has no prior point to compare against");
+
+        let one_second_later = start + Duration::from_secs(1);
+        let bytes_per_sec = rate.sample(one_second_later, 5_000_000);
+        assert!(
+            (bytes_per_sec - 5_000_000.0).abs() < 1.0,
+            "expected ~5MB/s, got {bytes_per_sec}"
+        );
+
+        let half_second_later = one_second_later + Duration::from_millis(500);
+        let bytes_per_sec = rate.sample(half_second_later, 6_500_000);
+        assert!(
+            (bytes_per_sec - 3_000_000.0).abs() < 1.0,
+            "expected ~3MB/s over the half-second window, got {bytes_per_sec}"
+        );
+    }
+
+    #[test]
+    fn overall_bar_is_hidden_outside_tty_modes() {
+        let ctx = ProgressContext::with_mode(ProgressMode::Plain);
+        let overall = ctx.overall_bar(4);
+        assert!(overall.is_hidden());
+
+        let ctx = ProgressContext::with_mode(ProgressMode::None);
+        let overall = ctx.overall_bar(4);
+        assert!(overall.is_hidden());
+    }
+}
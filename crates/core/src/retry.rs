@@ -0,0 +1,104 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// Backoff schedule for [`retry_with_backoff_policy`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let scaled = self.initial_delay.as_secs_f64() * self.multiplier.powi(attempt as i32);
+        Duration::from_secs_f64(scaled.min(self.max_delay.as_secs_f64()))
+    }
+}
+
+/// Retries `op` with the default [`RetryPolicy`].
+pub fn retry_with_backoff<T>(op: impl FnMut() -> Result<T>) -> Result<T> {
+    retry_with_backoff_policy(&RetryPolicy::default(), op)
+}
+
+/// Retries `op` up to `policy.max_attempts` times, sleeping with exponential
+/// backoff between attempts.
+pub fn retry_with_backoff_policy<T>(
+    policy: &RetryPolicy,
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut last_err = None;
+    for attempt in 0..policy.max_attempts {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt + 1 < policy.max_attempts => {
+                last_err = Some(err);
+                thread::sleep(policy.delay_for(attempt));
+            }
+            Err(err) => {
+                last_err = Some(err);
+                break;
+            }
+        }
+    }
+    match last_err {
+        Some(err) => Err(Error::RetryExhausted {
+            attempts: policy.max_attempts,
+            source: Box::new(err),
+        }),
+        // `policy.max_attempts == 0` means the loop above never ran `op` at
+        // all, so there's no source error to wrap.
+        None => Err(Error::Other(format!("retry policy allows {} attempts, so `op` was never called", policy.max_attempts))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_retry_preserves_attempt_count_and_last_error() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(0),
+            max_delay: Duration::from_millis(0),
+            multiplier: 1.0,
+        };
+
+        let err = retry_with_backoff_policy::<()>(&policy, || {
+            Err(Error::Other("distinctive failure".into()))
+        })
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "failed after 3 attempts: distinctive failure");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn zero_max_attempts_returns_an_error_instead_of_panicking() {
+        let policy = RetryPolicy {
+            max_attempts: 0,
+            initial_delay: Duration::from_millis(0),
+            max_delay: Duration::from_millis(0),
+            multiplier: 1.0,
+        };
+
+        let err = retry_with_backoff_policy::<()>(&policy, || Err(Error::Other("never called".into()))).unwrap_err();
+
+        assert!(!matches!(err, Error::RetryExhausted { .. }), "there's no attempt's error to wrap");
+    }
+}
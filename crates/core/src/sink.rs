@@ -0,0 +1,293 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::schema::types::ColumnPath;
+
+use crate::compression::Compression;
+use crate::error::Result;
+
+/// Suffix appended to a `ParquetSink`'s final path while it's being written.
+const TMP_SUFFIX: &str = ".tmp";
+
+/// Writes Arrow `RecordBatch`es to a parquet stream, compressing with the
+/// configured codec.
+///
+/// Constructed via [`ParquetSink::new`], a sink writes to a `.tmp` sibling
+/// of `path` first; [`ParquetSink::finalize`] fsyncs the file, atomically
+/// renames the sibling into place, then fsyncs the parent directory, so a reader
+/// never observes a partially written file. A sink dropped without calling
+/// `finalize` (e.g. on panic or early return) leaves only the `.tmp` file
+/// behind; [`cleanup_tmp_files`] removes those on restart.
+///
+/// [`ParquetSink::from_writer`] instead writes straight to an arbitrary
+/// `W: Write + Send` (a pipe, an object-store upload stream) with no
+/// filename or rename step of its own.
+///
+/// One `ParquetSink` corresponds to one output shard; callers open a new
+/// sink per shard and call [`ParquetSink::finalize`] when done.
+pub struct ParquetSink<W: Write + Send = File> {
+    writer: ArrowWriter<W>,
+    /// `(tmp_path, final_path)`, set only by the path-based constructors;
+    /// [`ParquetSink::finalize`] does the fsync-then-rename dance when
+    /// present, and just flushes otherwise.
+    rename: Option<(PathBuf, PathBuf)>,
+}
+
+impl ParquetSink<File> {
+    pub fn new(path: &Path, schema: Arc<arrow::datatypes::Schema>, compression: Compression) -> Result<Self> {
+        ParquetSink::new_with_disabled_stats(path, schema, compression, &[])
+    }
+
+    /// Like [`ParquetSink::new`], but skips writing min/max statistics for
+    /// `disabled_stats_columns` (by name). Large string columns (e.g.
+    /// abstracts) bloat the parquet footer with statistics that predicate
+    /// pushdown never uses; id/year-like columns keep arrow's default
+    /// (`EnabledStatistics::Page`) so pushdown still works for them.
+    pub fn new_with_disabled_stats(
+        path: &Path,
+        schema: Arc<arrow::datatypes::Schema>,
+        compression: Compression,
+        disabled_stats_columns: &[&str],
+    ) -> Result<Self> {
+        let tmp_path = tmp_path_for(path);
+        let file = File::create(&tmp_path)?;
+        let mut sink = ParquetSink::from_writer_with_disabled_stats(file, schema, compression, disabled_stats_columns)?;
+        sink.rename = Some((tmp_path, path.to_path_buf()));
+        Ok(sink)
+    }
+
+    /// Compatibility shim for callers still threading a bare zstd level.
+    pub fn create(path: &Path, schema: Arc<arrow::datatypes::Schema>, zstd_level: i32) -> Result<Self> {
+        ParquetSink::new(path, schema, Compression::Zstd(zstd_level))
+    }
+
+    /// Discards the sink after a failed write (e.g. disk full mid-batch),
+    /// removing its `.tmp` file immediately rather than leaving it for
+    /// [`cleanup_tmp_files`] to find on the next restart. The final path was
+    /// never touched, since [`ParquetSink::finalize`] only renames into place
+    /// on success.
+    pub fn abort(self) {
+        let tmp_path = self.rename.as_ref().map(|(tmp_path, _)| tmp_path.clone());
+        drop(self);
+        if let Some(tmp_path) = tmp_path {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+    }
+}
+
+impl<W: Write + Send> ParquetSink<W> {
+    /// Writes straight to `writer` instead of a path, for callers piping to
+    /// an object store or another process. There's no `.tmp` staging or
+    /// rename here; the caller owns whatever atomicity `writer` provides.
+    pub fn from_writer(writer: W, schema: Arc<arrow::datatypes::Schema>, compression: Compression) -> Result<Self> {
+        ParquetSink::from_writer_with_disabled_stats(writer, schema, compression, &[])
+    }
+
+    /// Like [`ParquetSink::from_writer`], with the same per-column
+    /// statistics control as [`ParquetSink::new_with_disabled_stats`].
+    pub fn from_writer_with_disabled_stats(
+        writer: W,
+        schema: Arc<arrow::datatypes::Schema>,
+        compression: Compression,
+        disabled_stats_columns: &[&str],
+    ) -> Result<Self> {
+        let mut builder = WriterProperties::builder().set_compression(compression.to_parquet()?);
+        for column in disabled_stats_columns {
+            builder = builder
+                .set_column_statistics_enabled(ColumnPath::from(vec![column.to_string()]), EnabledStatistics::None);
+        }
+        let props = builder.build();
+        let writer = ArrowWriter::try_new(writer, schema, Some(props))?;
+        Ok(ParquetSink { writer, rename: None })
+    }
+
+    pub fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        Ok(())
+    }
+
+    /// Writes the footer and flushes the underlying writer, returning it.
+    /// When the sink was opened by path ([`ParquetSink::new`]), also fsyncs
+    /// the `.tmp` file, atomically renames it into place at the configured
+    /// path, then fsyncs the parent directory.
+    pub fn finalize(self) -> Result<W> {
+        let rename = self.rename.clone();
+        let mut writer = self.writer.into_inner()?;
+        writer.flush()?;
+
+        if let Some((tmp_path, final_path)) = rename {
+            File::open(&tmp_path)?.sync_all()?;
+            std::fs::rename(&tmp_path, &final_path)?;
+            if let Some(parent) = final_path.parent() {
+                File::open(parent)?.sync_all()?;
+            }
+        }
+        Ok(writer)
+    }
+}
+
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(TMP_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Removes leftover `.tmp` files from `ParquetSink`s that were dropped
+/// without calling `finalize`, e.g. after a crash. Intended to run once on
+/// worker startup before any new sinks are opened.
+pub fn cleanup_tmp_files(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "tmp") {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, Int32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+
+    fn schema_and_batch() -> (Arc<Schema>, RecordBatch) {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1, 2, 3]))]).unwrap();
+        (schema, batch)
+    }
+
+    fn write_and_read_back(compression: Compression) -> i32 {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let (schema, batch) = schema_and_batch();
+
+        let mut sink = ParquetSink::new(&path, schema, compression).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.finalize().unwrap();
+
+        let file = File::open(&path).unwrap();
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap();
+        let read_back = reader.next().unwrap().unwrap();
+        read_back
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap()
+            .values()
+            .iter()
+            .sum()
+    }
+
+    #[test]
+    fn round_trips_with_zstd() {
+        assert_eq!(write_and_read_back(Compression::Zstd(15)), 6);
+    }
+
+    #[test]
+    fn round_trips_with_snappy() {
+        assert_eq!(write_and_read_back(Compression::Snappy), 6);
+    }
+
+    #[test]
+    fn dropping_without_finalize_leaves_only_the_tmp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let (schema, batch) = schema_and_batch();
+
+        let mut sink = ParquetSink::new(&path, schema, Compression::Snappy).unwrap();
+        sink.write_batch(&batch).unwrap();
+        drop(sink);
+
+        assert!(!path.exists(), "no valid final file should exist without finalize");
+        assert!(tmp_path_for(&path).exists());
+
+        cleanup_tmp_files(dir.path()).unwrap();
+        assert!(!tmp_path_for(&path).exists());
+    }
+
+    #[test]
+    fn disabled_stats_columns_have_no_footer_statistics() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("text", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(arrow::array::StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+
+        let mut sink = ParquetSink::new_with_disabled_stats(&path, schema, Compression::Snappy, &["text"]).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.finalize().unwrap();
+
+        let file = File::open(&path).unwrap();
+        let reader_builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let row_group = reader_builder.metadata().row_group(0);
+        let id_col = row_group.column(0);
+        let text_col = row_group.column(1);
+        assert!(id_col.statistics().is_some(), "id column should keep default statistics");
+        assert!(text_col.statistics().is_none(), "text column should have statistics disabled");
+    }
+
+    #[test]
+    fn from_writer_round_trips_through_an_in_memory_buffer() {
+        let (schema, batch) = schema_and_batch();
+
+        let mut sink = ParquetSink::from_writer(Vec::<u8>::new(), schema, Compression::Snappy).unwrap();
+        sink.write_batch(&batch).unwrap();
+        let buffer = sink.finalize().unwrap();
+
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(buffer))
+            .unwrap()
+            .build()
+            .unwrap();
+        let read_back = reader.next().unwrap().unwrap();
+        let sum: i32 = read_back
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap()
+            .values()
+            .iter()
+            .sum();
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn abort_after_a_failed_write_leaves_no_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let (schema, batch) = schema_and_batch();
+
+        let mut sink = ParquetSink::new(&path, schema, Compression::Snappy).unwrap();
+        sink.write_batch(&batch).unwrap();
+
+        let mismatched_schema = Arc::new(Schema::new(vec![Field::new("other", DataType::Utf8, false)]));
+        let mismatched_batch =
+            RecordBatch::try_new(mismatched_schema, vec![Arc::new(arrow::array::StringArray::from(vec!["x"]))])
+                .unwrap();
+        let write_result = sink.write_batch(&mismatched_batch);
+        assert!(write_result.is_err(), "a schema-mismatched batch should fail to write");
+
+        sink.abort();
+        assert!(!path.exists());
+        assert!(!tmp_path_for(&path).exists());
+    }
+}
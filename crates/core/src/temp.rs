@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::Result;
+
+/// Process-wide override of where temp/staging files are written, so a
+/// system with a small `/tmp` isn't forced to use it. Consulted by
+/// [`temp_dir`]; unset means [`std::env::temp_dir`].
+static GLOBAL_TEMP_DIR: OnceLock<Mutex<Option<PathBuf>>> = OnceLock::new();
+
+fn global_temp_dir() -> &'static Mutex<Option<PathBuf>> {
+    GLOBAL_TEMP_DIR.get_or_init(|| Mutex::new(None))
+}
+
+/// Overrides the process-wide temp directory returned by [`temp_dir`].
+pub fn set_temp_dir(path: impl Into<PathBuf>) {
+    *global_temp_dir().lock().unwrap_or_else(|e| e.into_inner()) = Some(path.into());
+}
+
+/// The directory new temp/staging files should be created under: whatever
+/// [`set_temp_dir`] last set, or [`std::env::temp_dir`] if it was never
+/// called.
+///
+/// [`crate::sink::ParquetSink`] doesn't consult this: its `.tmp` file is
+/// deliberately a sibling of its final output path so
+/// [`crate::sink::ParquetSink::finalize`]'s rename stays on one filesystem
+/// (a rename across filesystems isn't atomic, and can fail outright). This
+/// is for staging that isn't tied to a specific final path, e.g. a stream
+/// download's `.part` file or a join's scratch database.
+pub fn temp_dir() -> PathBuf {
+    global_temp_dir()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Sweeps stale `.tmp` and `.part` files out of [`temp_dir`]. Intended to
+/// run once on startup, before any new sinks or downloads begin, so a crash
+/// mid-run doesn't leave orphaned staging files behind indefinitely.
+pub fn cleanup_all_temp() -> Result<()> {
+    cleanup_temp_dir(&temp_dir())
+}
+
+fn cleanup_temp_dir(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "tmp" || ext == "part") {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Serializes tests below, since GLOBAL_TEMP_DIR is process-wide.
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn set_temp_dir_overrides_the_default() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+
+        set_temp_dir(dir.path());
+        assert_eq!(temp_dir(), dir.path());
+
+        set_temp_dir(std::env::temp_dir());
+    }
+
+    #[test]
+    fn cleanup_all_temp_removes_only_tmp_and_part_files() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("staged.tmp"), b"x").unwrap();
+        std::fs::write(dir.path().join("download.part"), b"x").unwrap();
+        std::fs::write(dir.path().join("keep.parquet"), b"x").unwrap();
+
+        set_temp_dir(dir.path());
+        cleanup_all_temp().unwrap();
+        set_temp_dir(std::env::temp_dir());
+
+        assert!(!dir.path().join("staged.tmp").exists());
+        assert!(!dir.path().join("download.part").exists());
+        assert!(dir.path().join("keep.parquet").exists());
+    }
+}
@@ -0,0 +1,88 @@
+//! Shared helpers for asserting on parquet output in tests, so crates don't
+//! each re-implement a mini reader (some via arrow directly, some by
+//! shelling out to DuckDB) just to check a row count or sample a column.
+//! Gated behind the `testutil` feature since it's dev-only surface.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::RecordBatchReader;
+use arrow::datatypes::Schema;
+use arrow::util::display::array_value_to_string;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::error::Result;
+
+/// Reads `path`'s schema and total row count across every row group.
+pub fn read_parquet_rows(path: &Path) -> Result<(Arc<Schema>, usize)> {
+    let file = File::open(path)?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+    let schema = reader.schema();
+
+    let mut rows = 0;
+    for batch in reader {
+        rows += batch?.num_rows();
+    }
+    Ok((schema, rows))
+}
+
+/// Reads every value of `column` across `path`'s row groups, formatted as
+/// strings for easy assertion. Errors if `column` doesn't exist.
+pub fn column_values(path: &Path, column: &str) -> Result<Vec<String>> {
+    let file = File::open(path)?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+
+    let mut values = Vec::new();
+    for batch in reader {
+        let batch = batch?;
+        let array = batch
+            .column_by_name(column)
+            .ok_or_else(|| crate::error::Error::Other(format!("no column named `{column}`")))?;
+        for row in 0..array.len() {
+            values.push(array_value_to_string(array.as_ref(), row)?);
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use arrow::record_batch::RecordBatch;
+
+    use super::*;
+    use crate::compression::Compression;
+    use crate::sink::ParquetSink;
+
+    #[test]
+    fn reads_back_row_count_and_column_values_from_a_written_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("n", DataType::Int32, false),
+            Field::new("label", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+
+        let mut sink = ParquetSink::new(&path, schema, Compression::Snappy).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.finalize().unwrap();
+
+        let (read_schema, rows) = read_parquet_rows(&path).unwrap();
+        assert_eq!(rows, 3);
+        assert_eq!(read_schema.field(1).name(), "label");
+
+        let labels = column_values(&path, "label").unwrap();
+        assert_eq!(labels, vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::error::Result;
+
+/// Default cap on how many attempts a shard gets in all (its first dequeue
+/// plus each [`WorkQueue::requeue`]) before it's treated as a permanent failure.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// A simple FIFO of work items shared across worker threads.
+///
+/// Each source crate shards its input (PubMed files, OpenAlex/S2 parquet
+/// parts) into items and hands them to a `WorkQueue`, then spawns
+/// `workers` threads that loop on [`WorkQueue::pop`] until it returns
+/// `None`. A worker that exhausts [`crate::retry::retry_with_backoff`] on a
+/// shard calls [`WorkQueue::requeue`] instead of counting it failed right
+/// away, so it gets another pass after the rest of the queue drains (which
+/// might free memory or let a transient outage recover), until it has been
+/// attempted `max_attempts` times in all.
+///
+/// Each item also gets a stable `index` (its position in the iterator
+/// passed to [`WorkQueue::new`]), reported alongside it by [`WorkQueue::pop`]
+/// and unaffected by requeuing. A worker that finishes a shard calls
+/// [`WorkQueue::mark_done`] with that index; [`WorkQueue::save_state`] then
+/// persists the done set so [`WorkQueue::load_state`] on the next run can
+/// skip re-dequeuing those shards in O(done shards) instead of re-scanning
+/// every shard's output for presence.
+pub struct WorkQueue<T> {
+    items: Mutex<VecDeque<(T, u32, usize)>>,
+    max_attempts: u32,
+    done: Mutex<HashSet<usize>>,
+}
+
+impl<T> WorkQueue<T> {
+    /// Builds a queue with the default requeue cap
+    /// ([`DEFAULT_MAX_ATTEMPTS`]).
+    pub fn new(items: impl IntoIterator<Item = T>) -> Self {
+        WorkQueue::with_max_attempts(items, DEFAULT_MAX_ATTEMPTS)
+    }
+
+    pub fn with_max_attempts(items: impl IntoIterator<Item = T>, max_attempts: u32) -> Self {
+        WorkQueue {
+            items: Mutex::new(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, item)| (item, 1, index))
+                    .collect(),
+            ),
+            max_attempts,
+            done: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Pops the next item along with its attempt number (starting at `1`)
+    /// and its stable index, or `None` once the queue is drained.
+    pub fn pop(&self) -> Option<(T, u32, usize)> {
+        self.items.lock().expect("work queue mutex poisoned").pop_front()
+    }
+
+    /// Puts `item` back at the end of the queue for another attempt, unless
+    /// `attempt` has already reached `max_attempts`. Returns `true` if the
+    /// item was requeued, `false` if the caller should count it as a
+    /// permanent failure instead.
+    pub fn requeue(&self, item: T, attempt: u32, index: usize) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        self.items
+            .lock()
+            .expect("work queue mutex poisoned")
+            .push_back((item, attempt + 1, index));
+        true
+    }
+
+    /// Records `index` as durably finished, for the next [`WorkQueue::save_state`].
+    pub fn mark_done(&self, index: usize) {
+        self.done.lock().expect("work queue mutex poisoned").insert(index);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().expect("work queue mutex poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the indices [`WorkQueue::mark_done`] has recorded to `path`,
+    /// one per line.
+    pub fn save_state(&self, path: &Path) -> Result<()> {
+        let done = self.done.lock().expect("work queue mutex poisoned");
+        let mut indices: Vec<usize> = done.iter().copied().collect();
+        indices.sort_unstable();
+        let contents = indices.iter().map(usize::to_string).collect::<Vec<_>>().join("\n");
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Loads the done set saved by [`WorkQueue::save_state`] at `path`
+    /// (a no-op if it doesn't exist, e.g. a fresh run) and removes matching
+    /// items from the queue, so they aren't dequeued again.
+    ///
+    /// Only calls `exists_on_disk` for items the saved state claims are
+    /// done — the rest of the queue is skipped without touching disk at
+    /// all, which is what keeps a restart fast. Reconciliation is
+    /// disk-wins: an index the state file marks done but whose
+    /// `exists_on_disk` comes back `false` (its output was deleted, or the
+    /// save happened just before a crash) is left on the queue for another
+    /// attempt instead of being trusted blindly.
+    pub fn load_state(&self, path: &Path, exists_on_disk: impl Fn(&T) -> bool) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let recorded_done: HashSet<usize> = contents.lines().filter_map(|line| line.trim().parse().ok()).collect();
+
+        let mut items = self.items.lock().expect("work queue mutex poisoned");
+        let mut done = self.done.lock().expect("work queue mutex poisoned");
+        items.retain(|(item, _attempt, index)| {
+            if recorded_done.contains(index) && exists_on_disk(item) {
+                done.insert(*index);
+                false
+            } else {
+                true
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_requeued_shard_is_retried_and_can_then_succeed() {
+        let queue = WorkQueue::new(["shard-a"]);
+
+        let (item, attempt, index) = queue.pop().unwrap();
+        assert_eq!(item, "shard-a");
+        assert_eq!(attempt, 1);
+        assert!(queue.is_empty(), "the item is out of the queue while being worked on");
+
+        assert!(queue.requeue(item, attempt, index), "should still be under the attempt cap");
+        assert_eq!(queue.len(), 1);
+
+        let (item, attempt, _index) = queue.pop().unwrap();
+        assert_eq!(item, "shard-a");
+        assert_eq!(attempt, 2, "the second dequeue is attempt 2");
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn requeue_stops_once_max_attempts_is_reached() {
+        let queue = WorkQueue::with_max_attempts(["shard-a"], 2);
+
+        let (item, attempt, index) = queue.pop().unwrap();
+        assert!(queue.requeue(item, attempt, index));
+
+        let (item, attempt, index) = queue.pop().unwrap();
+        assert_eq!(attempt, 2);
+        assert!(!queue.requeue(item, attempt, index), "attempt 2 already hit the cap of 2");
+        assert!(queue.is_empty(), "a permanently failed shard doesn't go back on the queue");
+    }
+
+    #[test]
+    fn save_and_load_state_skips_only_completed_shards() {
+        let queue = WorkQueue::new(["a", "b", "c"]);
+        let (item, _attempt, index) = queue.pop().unwrap();
+        assert_eq!(item, "a");
+        queue.mark_done(index);
+
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("state");
+        queue.save_state(&state_path).unwrap();
+
+        let reloaded = WorkQueue::new(["a", "b", "c"]);
+        reloaded.load_state(&state_path, |_| true).unwrap();
+
+        let mut remaining = Vec::new();
+        while let Some((item, _attempt, _index)) = reloaded.pop() {
+            remaining.push(item);
+        }
+        assert_eq!(remaining, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn load_state_requeues_a_done_shard_whose_output_went_missing() {
+        let queue = WorkQueue::new(["a", "b"]);
+        let (_item, _attempt, index) = queue.pop().unwrap();
+        queue.mark_done(index);
+
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("state");
+        queue.save_state(&state_path).unwrap();
+
+        let reloaded = WorkQueue::new(["a", "b"]);
+        reloaded.load_state(&state_path, |_| false).unwrap();
+
+        let mut remaining = Vec::new();
+        while let Some((item, _attempt, _index)) = reloaded.pop() {
+            remaining.push(item);
+        }
+        assert_eq!(remaining, vec!["a", "b"], "disk wins: a missing output means the shard must be redone");
+    }
+
+    #[test]
+    fn load_state_is_a_no_op_when_no_state_file_exists() {
+        let queue = WorkQueue::new(["a", "b"]);
+        let dir = tempfile::tempdir().unwrap();
+        queue.load_state(&dir.path().join("missing"), |_| true).unwrap();
+        assert_eq!(queue.len(), 2);
+    }
+}
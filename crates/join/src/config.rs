@@ -0,0 +1,307 @@
+use std::path::{Path, PathBuf};
+
+use duckdb::Connection;
+
+use crate::error::{Error, Result};
+
+/// Configuration for a join run.
+///
+/// Each source is named by a DuckDB-compatible glob passed straight into
+/// `read_parquet`, so the storage layout (one directory per source, or all
+/// sources interleaved under one tree with a naming convention) is entirely
+/// up to the caller.
+#[derive(Debug, Clone)]
+pub struct JoinConfig {
+    pub pubmed_glob: String,
+    pub openalex_glob: String,
+    pub s2_glob: String,
+    pub output_dir: PathBuf,
+    /// When set, also writes `author_edges.parquet`: undirected
+    /// co-authorship edges self-joined from each paper's author list.
+    pub export_coauthorship: bool,
+    /// Papers with more authors than this are skipped when building
+    /// co-authorship edges, to avoid a combinatorial blowup.
+    pub max_authors_per_paper: usize,
+    /// When set, `run` opens this file as an on-disk DuckDB database instead
+    /// of an in-memory one, and reuses `pubmed_keys`/`openalex_keys`/`s2_keys`
+    /// from a previous run if the source globs' content hashes haven't
+    /// changed, instead of rebuilding them.
+    pub persist_db: Option<PathBuf>,
+    /// When set, PMID matching requires an exact string match. Off by
+    /// default, since PMIDs vary across sources (stray whitespace, version
+    /// suffixes) and normalizing them (trim, drop a trailing `.N` version
+    /// suffix, strip non-digits) is what lets
+    /// the PMID fallback actually catch matches DOI joining misses.
+    pub strict_pmid: bool,
+    /// When set, also writes `anomalies.parquet`: DOIs on the PubMed side
+    /// that map to more than one distinct PMID, a data-quality red flag
+    /// that the join would otherwise resolve silently. Off by default.
+    pub report_anomalies: bool,
+    /// When set, a parquet or CSV file with a `pmid` column and arbitrary
+    /// extra columns (e.g. an institution's internal-id crosswalk),
+    /// LEFT JOINed onto `joined` by PMID. Extra columns pass through
+    /// prefixed with `xw_`. Errors if the file has no `pmid` column.
+    pub crosswalk: Option<PathBuf>,
+    /// How NULL and empty-string values in `joined`'s text columns should be
+    /// normalized before writing, since sources disagree (PubMed leaves a
+    /// missing value NULL, S2 coerces it to an empty string) and mixing
+    /// both breaks a downstream `IS NULL` check. Defaults to
+    /// [`NullStyle::Preserve`], which leaves each source's convention as-is.
+    pub null_style: NullStyle,
+    /// A SQL expression referencing `d` (the raw DOI), spliced into the
+    /// `normalize_doi` macro body in place of the bare `d` before it's
+    /// wrapped in `lower(trim(...))`, e.g. `regexp_replace(d, '^legacy:',
+    /// '')` to strip an institution-specific prefix. Validated by creating
+    /// the macro in a throwaway connection before the real run starts, so a
+    /// typo surfaces as a config error instead of a silent non-match.
+    /// Defaults to `d` (no extra normalization).
+    pub extra_doi_normalization: Option<String>,
+    /// When set, adds a `node_id` column to `joined`: a dense integer
+    /// (`0..N`) assigned by `ROW_NUMBER() OVER (ORDER BY pmid) - 1`, cheaper
+    /// to use as a graph vertex index than `pmid` once a citation-edge
+    /// export exists to reference it. Off by default.
+    pub assign_node_ids: bool,
+    /// When set, also writes `join_meta.json` to `output_dir`: the DuckDB
+    /// version and effective `memory_limit`/`threads` settings the run used,
+    /// for reproducibility audits. Off by default.
+    pub write_join_meta: bool,
+    /// When set, keeps only nodes whose language (PubMed's `language`,
+    /// falling back to OpenAlex's when PubMed's is missing, both normalized
+    /// through the `normalize_lang` macro from ISO 639-2 to 639-1) is one of
+    /// these codes, e.g. `["en"]`. When neither source has a `language`
+    /// column, every node is kept. `None` (the default) keeps every node
+    /// regardless of language.
+    pub languages: Option<Vec<String>>,
+    /// When set, runs an additional match pass after DOI/PMID: nodes still
+    /// missing an `openalex_id` are matched to an OpenAlex row by
+    /// normalized PMCID (PubMed's `pmc_id` against OpenAlex's `pmcid`,
+    /// stripped of a `PMC` prefix and trimmed). Off by default, since
+    /// `pmc_id`/`pmcid` aren't present in every export.
+    pub match_pmcid: bool,
+    /// DuckDB's `memory_limit` setting for the join connection, e.g.
+    /// `"4GB"`. `None` (the default) leaves DuckDB's own default in place.
+    /// Validated by [`JoinConfigBuilder::build`], which applies it to a
+    /// throwaway connection before the real run starts, so a malformed
+    /// value surfaces as a config error instead of failing deep into
+    /// [`crate::run::run`].
+    pub memory_limit: Option<String>,
+}
+
+/// See [`JoinConfig::null_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullStyle {
+    #[default]
+    Preserve,
+    EmptyAsNull,
+    NullAsEmpty,
+}
+
+impl JoinConfig {
+    /// Dir-per-source layout: reads every `.parquet` file directly under
+    /// each directory.
+    pub fn from_dirs(
+        pubmed_dir: impl AsRef<Path>,
+        openalex_dir: impl AsRef<Path>,
+        s2_dir: impl AsRef<Path>,
+        output_dir: PathBuf,
+    ) -> Self {
+        JoinConfig::from_globs(
+            dir_glob(pubmed_dir),
+            dir_glob(openalex_dir),
+            dir_glob(s2_dir),
+            output_dir,
+        )
+    }
+
+    /// Single-tree layout: each glob is passed straight into `read_parquet`,
+    /// so a shared directory distinguished by filename prefix works fine.
+    pub fn from_globs(
+        pubmed_glob: impl Into<String>,
+        openalex_glob: impl Into<String>,
+        s2_glob: impl Into<String>,
+        output_dir: PathBuf,
+    ) -> Self {
+        JoinConfig {
+            pubmed_glob: pubmed_glob.into(),
+            openalex_glob: openalex_glob.into(),
+            s2_glob: s2_glob.into(),
+            output_dir,
+            export_coauthorship: false,
+            max_authors_per_paper: 50,
+            persist_db: None,
+            strict_pmid: false,
+            report_anomalies: false,
+            crosswalk: None,
+            null_style: NullStyle::default(),
+            extra_doi_normalization: None,
+            assign_node_ids: false,
+            write_join_meta: false,
+            languages: None,
+            match_pmcid: false,
+            memory_limit: None,
+        }
+    }
+
+    /// Starts building a [`JoinConfig`] via [`JoinConfigBuilder`], for
+    /// callers setting several optional fields at once (the CLI, tests)
+    /// instead of constructing the full struct or mutating a `from_globs`
+    /// result field by field.
+    pub fn builder(
+        pubmed_dir: impl AsRef<Path>,
+        openalex_dir: impl AsRef<Path>,
+        s2_dir: impl AsRef<Path>,
+        output_dir: PathBuf,
+    ) -> JoinConfigBuilder {
+        JoinConfigBuilder {
+            config: JoinConfig::from_dirs(pubmed_dir, openalex_dir, s2_dir, output_dir),
+        }
+    }
+}
+
+/// Builder for [`JoinConfig`], returned by [`JoinConfig::builder`]. Every
+/// optional [`JoinConfig`] field keeps its `from_globs` default until set;
+/// [`JoinConfigBuilder::build`] validates `memory_limit` before returning.
+#[derive(Debug, Clone)]
+pub struct JoinConfigBuilder {
+    config: JoinConfig,
+}
+
+impl JoinConfigBuilder {
+    pub fn export_coauthorship(mut self, export_coauthorship: bool) -> Self {
+        self.config.export_coauthorship = export_coauthorship;
+        self
+    }
+
+    pub fn max_authors_per_paper(mut self, max_authors_per_paper: usize) -> Self {
+        self.config.max_authors_per_paper = max_authors_per_paper;
+        self
+    }
+
+    pub fn persist_db(mut self, persist_db: PathBuf) -> Self {
+        self.config.persist_db = Some(persist_db);
+        self
+    }
+
+    pub fn strict_pmid(mut self, strict_pmid: bool) -> Self {
+        self.config.strict_pmid = strict_pmid;
+        self
+    }
+
+    pub fn report_anomalies(mut self, report_anomalies: bool) -> Self {
+        self.config.report_anomalies = report_anomalies;
+        self
+    }
+
+    pub fn crosswalk(mut self, crosswalk: PathBuf) -> Self {
+        self.config.crosswalk = Some(crosswalk);
+        self
+    }
+
+    pub fn null_style(mut self, null_style: NullStyle) -> Self {
+        self.config.null_style = null_style;
+        self
+    }
+
+    pub fn extra_doi_normalization(mut self, extra_doi_normalization: impl Into<String>) -> Self {
+        self.config.extra_doi_normalization = Some(extra_doi_normalization.into());
+        self
+    }
+
+    pub fn assign_node_ids(mut self, assign_node_ids: bool) -> Self {
+        self.config.assign_node_ids = assign_node_ids;
+        self
+    }
+
+    pub fn write_join_meta(mut self, write_join_meta: bool) -> Self {
+        self.config.write_join_meta = write_join_meta;
+        self
+    }
+
+    pub fn languages(mut self, languages: Vec<String>) -> Self {
+        self.config.languages = Some(languages);
+        self
+    }
+
+    pub fn match_pmcid(mut self, match_pmcid: bool) -> Self {
+        self.config.match_pmcid = match_pmcid;
+        self
+    }
+
+    pub fn memory_limit(mut self, memory_limit: impl Into<String>) -> Self {
+        self.config.memory_limit = Some(memory_limit.into());
+        self
+    }
+
+    /// Validates `memory_limit` (if set) against a throwaway in-memory
+    /// connection and returns the assembled [`JoinConfig`].
+    pub fn build(self) -> Result<JoinConfig> {
+        if let Some(limit) = &self.config.memory_limit {
+            validate_memory_limit(limit)?;
+        }
+        Ok(self.config)
+    }
+}
+
+/// Applies `limit` to a throwaway in-memory connection to catch a malformed
+/// `memory_limit` value (e.g. a missing unit) up front, instead of failing
+/// once [`crate::run::run`] opens the real connection.
+fn validate_memory_limit(limit: &str) -> Result<()> {
+    let probe = Connection::open_in_memory()?;
+    probe
+        .execute_batch(&format!("SET memory_limit = '{limit}';"))
+        .map_err(|e| Error::Other(format!("invalid memory_limit {limit:?}: {e}")))
+}
+
+fn dir_glob(dir: impl AsRef<Path>) -> String {
+    format!("{}/*.parquet", dir.as_ref().display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_minimal_builder_matches_the_default_filled_struct() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path().join("out");
+
+        let built = JoinConfig::builder(dir.path(), dir.path(), dir.path(), output_dir.clone())
+            .build()
+            .unwrap();
+        let by_hand = JoinConfig::from_dirs(dir.path(), dir.path(), dir.path(), output_dir);
+
+        assert_eq!(built.pubmed_glob, by_hand.pubmed_glob);
+        assert_eq!(built.output_dir, by_hand.output_dir);
+        assert_eq!(built.export_coauthorship, by_hand.export_coauthorship);
+        assert_eq!(built.max_authors_per_paper, by_hand.max_authors_per_paper);
+        assert_eq!(built.null_style, by_hand.null_style);
+        assert_eq!(built.memory_limit, by_hand.memory_limit);
+    }
+
+    #[test]
+    fn builder_wires_up_the_configured_optional_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = JoinConfig::builder(dir.path(), dir.path(), dir.path(), dir.path().join("out"))
+            .strict_pmid(true)
+            .match_pmcid(true)
+            .languages(vec!["en".to_string()])
+            .memory_limit("512MB")
+            .build()
+            .unwrap();
+
+        assert!(config.strict_pmid);
+        assert!(config.match_pmcid);
+        assert_eq!(config.languages, Some(vec!["en".to_string()]));
+        assert_eq!(config.memory_limit.as_deref(), Some("512MB"));
+    }
+
+    #[test]
+    fn build_rejects_a_malformed_memory_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = JoinConfig::builder(dir.path(), dir.path(), dir.path(), dir.path().join("out"))
+            .memory_limit("not a size")
+            .build();
+
+        assert!(result.is_err());
+    }
+}
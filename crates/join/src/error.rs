@@ -0,0 +1,14 @@
+/// Error type for the join stage.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("duckdb error: {0}")]
+    DuckDb(#[from] duckdb::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,13 @@
+//! Join PubMed, OpenAlex, and S2 parquet outputs into one corpus, keyed on
+//! normalized DOI (with a normalized-PMID fallback in the same join).
+//!
+//! The heavy lifting runs in DuckDB: each source is read straight off disk
+//! with `read_parquet`, reduced to a small key table, then joined.
+
+pub mod config;
+pub mod error;
+pub mod run;
+
+pub use config::{JoinConfig, NullStyle};
+pub use error::{Error, Result};
+pub use run::{run, run_with_progress, JoinSummary};
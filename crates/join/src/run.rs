@@ -0,0 +1,1259 @@
+use duckdb::{Connection, OptionalExt};
+use serde::Serialize;
+
+use crate::config::{JoinConfig, NullStyle};
+use crate::error::{Error, Result};
+
+/// Reproducibility metadata captured at the start of a [`run`], describing
+/// the DuckDB build and settings that produced the output. Written to
+/// `join_meta.json` when [`JoinConfig::write_join_meta`] is set.
+#[derive(Debug, Clone, Serialize)]
+pub struct JoinSummary {
+    pub duckdb_version: String,
+    pub effective_memory: String,
+    pub effective_threads: i64,
+    /// Nodes matched to an OpenAlex row only by the [`JoinConfig::match_pmcid`]
+    /// pass, i.e. that DOI and PMID matching both missed. `0` when
+    /// `match_pmcid` is off.
+    pub openalex_pmcid_matches: i64,
+}
+
+fn capture_summary(conn: &Connection) -> Result<JoinSummary> {
+    let duckdb_version: String = conn.query_row("SELECT version();", [], |row| row.get(0))?;
+    let effective_memory: String = conn.query_row("SELECT current_setting('memory_limit');", [], |row| row.get(0))?;
+    let effective_threads: i64 = conn.query_row("SELECT current_setting('threads');", [], |row| row.get(0))?;
+    Ok(JoinSummary {
+        duckdb_version,
+        effective_memory,
+        effective_threads,
+        openalex_pmcid_matches: 0,
+    })
+}
+
+/// Runs the join: builds a normalized key table per source, joins them on
+/// DOI (falling back to normalized PMID when DOI is missing or doesn't
+/// match), and writes the result to `config.output_dir`. If
+/// `config.export_coauthorship` is set, also writes `author_edges.parquet`.
+/// If `config.match_pmcid` is set, nodes still missing an `openalex_id`
+/// after DOI/PMID matching are given a second chance via normalized PMCID,
+/// with the match count recorded on [`JoinSummary::openalex_pmcid_matches`].
+/// If `config.languages` is set, nodes whose PubMed (falling back to
+/// OpenAlex) language, normalized to ISO 639-1, isn't in the set are dropped
+/// right after the join. If `config.crosswalk` is set, its columns are
+/// attached to `joined` by PMID before the write step. If
+/// `config.null_style` isn't [`NullStyle::Preserve`], every text column in
+/// `joined` is normalized to that style before writing. If
+/// `config.extra_doi_normalization` is set, it's spliced into the
+/// `normalize_doi` macro body and validated in a throwaway connection before
+/// the real run uses it. If `config.write_join_meta` is set, also writes
+/// `join_meta.json` capturing the returned [`JoinSummary`].
+///
+/// `on_step` is called with a short name before each step starts, so callers
+/// can drive a progress bar or assert on which steps actually ran.
+pub fn run(config: &JoinConfig, on_step: impl FnMut(&str)) -> Result<JoinSummary> {
+    run_with_progress(config, on_step, no_progress)
+}
+
+fn no_progress(_step: &str, _fraction: f64) {}
+
+/// Like [`run`], but also calls `on_progress(step, fraction)` as the
+/// per-source key tables are built, one source file at a time, so a step
+/// spanning many large parquet files doesn't sit at 0% for minutes. `step`
+/// matches the name `on_step` received for that step (`pubmed_keys`,
+/// `openalex_keys`, `s2_keys`); `fraction` runs from just above `0.0` to
+/// `1.0` as each file finishes.
+pub fn run_with_progress(
+    config: &JoinConfig,
+    mut on_step: impl FnMut(&str),
+    mut on_progress: impl FnMut(&str, f64),
+) -> Result<JoinSummary> {
+    let doi_expr = config.extra_doi_normalization.as_deref().unwrap_or("d");
+    if config.extra_doi_normalization.is_some() {
+        validate_extra_doi_normalization(doi_expr)?;
+    }
+
+    let conn = open_connection(config)?;
+    let mut summary = capture_summary(&conn)?;
+    conn.execute_batch(&format!(
+        "CREATE MACRO IF NOT EXISTS normalize_doi(d) AS lower(trim({doi_expr}));
+         CREATE MACRO IF NOT EXISTS normalize_pmid(p) AS
+             NULLIF(regexp_replace(regexp_replace(trim(CAST(p AS VARCHAR)), '[.][0-9]+$', ''), '[^0-9]', '', 'g'), '');
+         CREATE MACRO IF NOT EXISTS normalize_lang(l) AS
+             CASE lower(trim(l))
+                 WHEN 'eng' THEN 'en'
+                 WHEN 'fre' THEN 'fr'
+                 WHEN 'ger' THEN 'de'
+                 WHEN 'spa' THEN 'es'
+                 WHEN 'ita' THEN 'it'
+                 WHEN 'jpn' THEN 'ja'
+                 WHEN 'chi' THEN 'zh'
+                 WHEN 'rus' THEN 'ru'
+                 ELSE lower(trim(l))
+             END;
+         CREATE TABLE IF NOT EXISTS papeline_join_meta (
+             source VARCHAR PRIMARY KEY,
+             content_hash VARCHAR NOT NULL
+         );"
+    ))?;
+
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE VIEW pubmed AS SELECT * FROM read_parquet('{}');
+         CREATE OR REPLACE VIEW openalex AS SELECT * FROM read_parquet('{}');
+         CREATE OR REPLACE VIEW s2 AS SELECT * FROM read_parquet('{}');",
+        quote_glob(&config.pubmed_glob),
+        quote_glob(&config.openalex_glob),
+        quote_glob(&config.s2_glob),
+    ))?;
+
+    let reuse_keys = config.persist_db.is_some() && can_reuse_keys(&conn, config)?;
+
+    if reuse_keys {
+        on_step("reuse_keys");
+    } else {
+        let pmid_norm = pmid_norm_expr(config.strict_pmid);
+
+        on_step("pubmed_keys");
+        build_key_table(&conn, "pubmed_keys", &config.pubmed_glob, pmid_norm, "title", &mut on_progress)?;
+        record_hash(&conn, "pubmed", &glob_content_hash(&conn, &config.pubmed_glob)?)?;
+
+        on_step("openalex_keys");
+        build_key_table(
+            &conn,
+            "openalex_keys",
+            &config.openalex_glob,
+            pmid_norm,
+            "openalex_id",
+            &mut on_progress,
+        )?;
+        record_hash(&conn, "openalex", &glob_content_hash(&conn, &config.openalex_glob)?)?;
+
+        on_step("s2_keys");
+        build_key_table(&conn, "s2_keys", &config.s2_glob, pmid_norm, "s2_id", &mut on_progress)?;
+        record_hash(&conn, "s2", &glob_content_hash(&conn, &config.s2_glob)?)?;
+    }
+
+    on_step("join");
+    conn.execute_batch(
+        "CREATE OR REPLACE TABLE joined AS
+         SELECT
+             COALESCE(p.doi, oa.doi, s2.doi) AS doi,
+             COALESCE(p.pmid, oa.pmid, s2.pmid) AS pmid,
+             p.title AS pubmed_title,
+             oa.openalex_id,
+             s2.s2_id
+         FROM pubmed_keys p
+         FULL OUTER JOIN openalex_keys oa
+             ON oa.doi = p.doi
+             OR (oa.pmid_norm = p.pmid_norm AND oa.pmid_norm IS NOT NULL)
+         FULL OUTER JOIN s2_keys s2
+             ON s2.doi = COALESCE(p.doi, oa.doi)
+             OR (s2.pmid_norm = COALESCE(p.pmid_norm, oa.pmid_norm) AND s2.pmid_norm IS NOT NULL);",
+    )?;
+
+    if config.match_pmcid {
+        summary.openalex_pmcid_matches = attach_pmcid_bridge(&conn, &mut on_step)?;
+    }
+
+    if let Some(languages) = &config.languages {
+        apply_language_filter(&conn, languages, &mut on_step)?;
+    }
+
+    if let Some(crosswalk) = &config.crosswalk {
+        attach_crosswalk(&conn, crosswalk, &mut on_step)?;
+    }
+
+    if config.null_style != NullStyle::Preserve {
+        normalize_null_style(&conn, config.null_style, &mut on_step)?;
+    }
+
+    if config.assign_node_ids {
+        assign_node_ids(&conn, &mut on_step)?;
+    }
+
+    on_step("write");
+    std::fs::create_dir_all(&config.output_dir)?;
+    conn.execute_batch(&format!(
+        "COPY joined TO '{}' (FORMAT PARQUET);",
+        quote_glob(&config.output_dir.join("joined.parquet").display().to_string())
+    ))?;
+
+    if config.export_coauthorship {
+        export_coauthor_edges(&conn, config, &mut on_step)?;
+    }
+
+    if config.report_anomalies {
+        export_anomalies(&conn, config, &mut on_step)?;
+    }
+
+    if config.write_join_meta {
+        on_step("join_meta");
+        let json = serde_json::to_string_pretty(&summary).map_err(|e| Error::Other(e.to_string()))?;
+        std::fs::write(config.output_dir.join("join_meta.json"), json)?;
+    }
+
+    Ok(summary)
+}
+
+/// Flags DOIs on the PubMed side that map to more than one distinct PMID —
+/// normally the join just picks whichever `pubmed_keys` row wins the
+/// dedup, silently hiding a data-quality problem. Writes one row per
+/// anomalous DOI with its competing PMIDs collected into a list.
+fn export_anomalies(conn: &Connection, config: &JoinConfig, on_step: &mut impl FnMut(&str)) -> Result<()> {
+    on_step("anomalies");
+    conn.execute_batch(
+        "CREATE OR REPLACE TABLE anomalies AS
+         SELECT doi, list(DISTINCT pmid) AS competing_pmids, count(DISTINCT pmid) AS pmid_count
+         FROM pubmed_keys
+         WHERE doi IS NOT NULL AND pmid IS NOT NULL
+         GROUP BY doi
+         HAVING count(DISTINCT pmid) > 1;",
+    )?;
+
+    conn.execute_batch(&format!(
+        "COPY anomalies TO '{}' (FORMAT PARQUET);",
+        quote_glob(&config.output_dir.join("anomalies.parquet").display().to_string())
+    ))?;
+
+    Ok(())
+}
+
+/// LEFT JOINs `crosswalk` onto `joined` by PMID, prefixing every column
+/// besides `pmid` with `xw_`. Errors if the crosswalk has no `pmid` column,
+/// since there'd be nothing to join on.
+fn attach_crosswalk(conn: &Connection, crosswalk: &std::path::Path, on_step: &mut impl FnMut(&str)) -> Result<()> {
+    on_step("crosswalk");
+
+    let source_expr = match crosswalk.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => format!("read_csv_auto('{}')", quote_glob(&crosswalk.display().to_string())),
+        _ => format!("read_parquet('{}')", quote_glob(&crosswalk.display().to_string())),
+    };
+    conn.execute_batch(&format!("CREATE OR REPLACE VIEW crosswalk_raw AS SELECT * FROM {source_expr};"))?;
+
+    if !has_column(conn, "crosswalk_raw", "pmid")? {
+        return Err(Error::Other(format!(
+            "crosswalk {} has no `pmid` column",
+            crosswalk.display()
+        )));
+    }
+
+    let extra_columns: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('crosswalk_raw') WHERE name <> 'pmid'")?
+        .query_map([], |row| row.get(0))?
+        .collect::<duckdb::Result<_>>()?;
+
+    if extra_columns.is_empty() {
+        return Ok(());
+    }
+
+    let select_list = extra_columns
+        .iter()
+        .map(|c| format!("xw.\"{c}\" AS xw_{c}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TABLE joined AS
+         SELECT joined.*, {select_list}
+         FROM joined
+         LEFT JOIN crosswalk_raw xw
+             ON CAST(xw.pmid AS VARCHAR) = CAST(joined.pmid AS VARCHAR);"
+    ))?;
+
+    Ok(())
+}
+
+/// Rewrites every VARCHAR column of `joined` to `style`'s convention:
+/// [`NullStyle::EmptyAsNull`] turns `''` into `NULL`, [`NullStyle::NullAsEmpty`]
+/// turns `NULL` into `''`. Other column types pass through unchanged.
+fn normalize_null_style(conn: &Connection, style: NullStyle, on_step: &mut impl FnMut(&str)) -> Result<()> {
+    on_step("normalize_nulls");
+
+    let columns: Vec<(String, String)> = conn
+        .prepare("SELECT name, type FROM pragma_table_info('joined')")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<duckdb::Result<_>>()?;
+
+    let select_list = columns
+        .iter()
+        .map(|(name, ty)| {
+            if ty.starts_with("VARCHAR") {
+                match style {
+                    NullStyle::EmptyAsNull => format!("NULLIF(\"{name}\", '') AS \"{name}\""),
+                    NullStyle::NullAsEmpty => format!("COALESCE(\"{name}\", '') AS \"{name}\""),
+                    NullStyle::Preserve => format!("\"{name}\""),
+                }
+            } else {
+                format!("\"{name}\"")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    conn.execute_batch(&format!("CREATE OR REPLACE TABLE joined AS SELECT {select_list} FROM joined;"))?;
+    Ok(())
+}
+
+/// Drops rows from `joined` whose language (PubMed's `language`, falling
+/// back to OpenAlex's when PubMed's is missing or that column isn't
+/// present) doesn't normalize to one of `languages`. If neither source has
+/// a `language` column — true of every real run today, since neither
+/// `papeline-pubmed`'s schema nor any OpenAlex export in this tree emits
+/// one — this is a no-op instead of running the filter: `COALESCE` of two
+/// `NULL` literals is `NULL`, and `WHERE NULL IN (...)` would drop every
+/// row rather than leaving `joined` alone.
+fn apply_language_filter(conn: &Connection, languages: &[String], on_step: &mut impl FnMut(&str)) -> Result<()> {
+    on_step("language_filter");
+
+    let pubmed_has_language = has_column(conn, "pubmed", "language")?;
+    let openalex_has_language = has_column(conn, "openalex", "language")?;
+    if !pubmed_has_language && !openalex_has_language {
+        return Ok(());
+    }
+
+    let pubmed_language = if pubmed_has_language { "p.language" } else { "NULL" };
+    let openalex_language = if openalex_has_language { "oa.language" } else { "NULL" };
+
+    conn.execute(
+        &format!(
+            "CREATE OR REPLACE TABLE joined AS
+             SELECT joined.*
+             FROM joined
+             LEFT JOIN pubmed p ON p.pmid = joined.pmid
+             LEFT JOIN openalex oa ON oa.pmid = joined.pmid
+             WHERE normalize_lang(COALESCE({pubmed_language}, {openalex_language})) IN ({});",
+            languages.iter().map(|_| "?").collect::<Vec<_>>().join(", ")
+        ),
+        duckdb::params_from_iter(languages),
+    )?;
+
+    Ok(())
+}
+
+/// Matches nodes still missing an `openalex_id` to an OpenAlex row by
+/// normalized PMCID, a second bridge after DOI/PMID. A no-op (returning
+/// `0`) if either source lacks a `pmc_id`/`pmcid` column. Returns the
+/// number of nodes newly matched this way.
+fn attach_pmcid_bridge(conn: &Connection, on_step: &mut impl FnMut(&str)) -> Result<i64> {
+    if !has_column(conn, "pubmed", "pmc_id")? || !has_column(conn, "openalex", "pmcid")? {
+        return Ok(0);
+    }
+
+    on_step("pmcid_bridge");
+    conn.execute_batch(
+        "CREATE MACRO IF NOT EXISTS normalize_pmcid(p) AS
+             NULLIF(regexp_replace(upper(trim(CAST(p AS VARCHAR))), '^PMC', ''), '');
+
+         CREATE OR REPLACE TABLE pmcid_matches AS
+         SELECT joined.pmid AS pmid, oa.openalex_id AS openalex_id
+         FROM joined
+         JOIN pubmed p ON p.pmid = joined.pmid
+         JOIN openalex oa ON normalize_pmcid(oa.pmcid) = normalize_pmcid(p.pmc_id)
+         WHERE joined.openalex_id IS NULL AND normalize_pmcid(p.pmc_id) IS NOT NULL;",
+    )?;
+
+    let matched: i64 = conn.query_row("SELECT count(*) FROM pmcid_matches", [], |row| row.get(0))?;
+    if matched > 0 {
+        conn.execute_batch(
+            "CREATE OR REPLACE TABLE joined AS
+             SELECT joined.* EXCLUDE (openalex_id),
+                    COALESCE(joined.openalex_id, m.openalex_id) AS openalex_id
+             FROM joined
+             LEFT JOIN pmcid_matches m ON m.pmid = joined.pmid;",
+        )?;
+    }
+
+    Ok(matched)
+}
+
+/// Adds a dense `node_id` column (`0..N`, ordered by `pmid`) to `joined`,
+/// for callers that want a compact vertex index instead of using `pmid`
+/// directly. There's no citation-edge export in this crate yet to join
+/// against these ids; this just makes them available on the node table.
+fn assign_node_ids(conn: &Connection, on_step: &mut impl FnMut(&str)) -> Result<()> {
+    on_step("assign_node_ids");
+    conn.execute_batch(
+        "CREATE OR REPLACE TABLE joined AS
+         SELECT ROW_NUMBER() OVER (ORDER BY pmid) - 1 AS node_id, joined.*
+         FROM joined;",
+    )?;
+    Ok(())
+}
+
+/// Self-joins each paper's author list (from S2 `paper_authors` and/or
+/// OpenAlex `author_ids`, whichever columns are present) into undirected
+/// co-authorship edges, skipping papers with more than
+/// `max_authors_per_paper` authors to avoid a combinatorial blowup.
+fn export_coauthor_edges(
+    conn: &Connection,
+    config: &JoinConfig,
+    on_step: &mut impl FnMut(&str),
+) -> Result<()> {
+    let mut sources = Vec::new();
+    if has_column(conn, "s2", "paper_authors")? {
+        sources.push("SELECT pmid, paper_authors AS authors FROM s2 WHERE paper_authors IS NOT NULL".to_string());
+    }
+    if has_column(conn, "openalex", "author_ids")? {
+        sources.push("SELECT pmid, author_ids AS authors FROM openalex WHERE author_ids IS NOT NULL".to_string());
+    }
+    if sources.is_empty() {
+        return Ok(());
+    }
+
+    on_step("author_edges");
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TABLE paper_authors AS
+         SELECT pmid, authors FROM ({}) t
+         WHERE len(authors) <= {};
+
+         CREATE OR REPLACE TABLE author_edges AS
+         SELECT DISTINCT
+             LEAST(a.value, b.value) AS author_a,
+             GREATEST(a.value, b.value) AS author_b,
+             pmid AS shared_paper_pmid
+         FROM paper_authors
+         CROSS JOIN UNNEST(authors) AS a(value)
+         CROSS JOIN UNNEST(authors) AS b(value)
+         WHERE a.value < b.value;",
+        sources.join(" UNION ALL "),
+        config.max_authors_per_paper,
+    ))?;
+
+    conn.execute_batch(&format!(
+        "COPY author_edges TO '{}' (FORMAT PARQUET);",
+        quote_glob(&config.output_dir.join("author_edges.parquet").display().to_string())
+    ))?;
+
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        &format!("SELECT count(*) FROM pragma_table_info('{table}') WHERE name = '{column}'"),
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT count(*) FROM information_schema.tables WHERE table_name = ?",
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Opens `config.persist_db` on disk if set, else an in-memory database.
+fn open_connection(config: &JoinConfig) -> Result<Connection> {
+    let conn = match &config.persist_db {
+        Some(path) => Connection::open(path)?,
+        None => Connection::open_in_memory()?,
+    };
+    if let Some(limit) = &config.memory_limit {
+        conn.execute_batch(&format!("SET memory_limit = '{limit}';"))?;
+    }
+    Ok(conn)
+}
+
+/// Creates `normalize_doi` in a throwaway in-memory connection to catch a
+/// malformed [`JoinConfig::extra_doi_normalization`] expression up front,
+/// instead of failing deep into the real run once `pubmed_keys` starts
+/// building.
+fn validate_extra_doi_normalization(expr: &str) -> Result<()> {
+    let probe = Connection::open_in_memory()?;
+    probe
+        .execute_batch(&format!("CREATE MACRO normalize_doi(d) AS lower(trim({expr}));"))
+        .map_err(|e| Error::Other(format!("invalid extra_doi_normalization expression: {e}")))
+}
+
+/// Builds `table` (`doi`, `pmid`, `pmid_norm`, plus `select_extra`) one file
+/// of `glob` at a time instead of one `read_parquet(glob)` scan, so
+/// `on_progress` can report a fraction as each file finishes.
+fn build_key_table(
+    conn: &Connection,
+    table: &str,
+    glob: &str,
+    pmid_norm: &str,
+    select_extra: &str,
+    on_progress: &mut impl FnMut(&str, f64),
+) -> Result<()> {
+    let files: Vec<String> = conn
+        .prepare("SELECT file FROM glob(?) ORDER BY file")?
+        .query_map([glob], |row| row.get(0))?
+        .collect::<duckdb::Result<_>>()?;
+
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TABLE {table} (doi VARCHAR, pmid BIGINT, pmid_norm VARCHAR, {select_extra} VARCHAR);"
+    ))?;
+
+    if files.is_empty() {
+        on_progress(table, 1.0);
+        return Ok(());
+    }
+
+    for (index, file) in files.iter().enumerate() {
+        conn.execute_batch(&format!(
+            "INSERT INTO {table}
+             SELECT normalize_doi(doi) AS doi, pmid, {pmid_norm} AS pmid_norm, {select_extra}
+             FROM read_parquet('{}')
+             WHERE doi IS NOT NULL OR pmid IS NOT NULL;",
+            quote_glob(file)
+        ))?;
+        on_progress(table, (index + 1) as f64 / files.len() as f64);
+    }
+    Ok(())
+}
+
+/// Hashes the content of every file matched by `glob`, combined order
+/// sensitively, to detect whether a source has changed since it was last
+/// used to build `*_keys` in a persisted database.
+fn glob_content_hash(conn: &Connection, glob: &str) -> Result<String> {
+    let files: Vec<String> = conn
+        .prepare("SELECT file FROM glob(?) ORDER BY file")?
+        .query_map([glob], |row| row.get(0))?
+        .collect::<duckdb::Result<_>>()?;
+
+    let mut hashes = Vec::with_capacity(files.len());
+    for file in &files {
+        let bytes = std::fs::read(file)?;
+        hashes.push(papeline_core::hash::hash_bytes(&bytes));
+    }
+    Ok(papeline_core::hash::combine_hashes(hashes.iter().map(String::as_str)))
+}
+
+/// Looks up the content hash recorded for `source` the last time its key
+/// table was built, if any.
+fn stored_hash(conn: &Connection, source: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT content_hash FROM papeline_join_meta WHERE source = ?",
+        [source],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(Into::into)
+}
+
+fn record_hash(conn: &Connection, source: &str, content_hash: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO papeline_join_meta (source, content_hash) VALUES (?, ?)",
+        duckdb::params![source, content_hash],
+    )?;
+    Ok(())
+}
+
+/// Whether `pubmed_keys`/`openalex_keys`/`s2_keys` can be reused from a
+/// persisted database: all three tables must already exist and their
+/// recorded source content hash must match the current glob contents.
+fn can_reuse_keys(conn: &Connection, config: &JoinConfig) -> Result<bool> {
+    for (source, glob, table) in [
+        ("pubmed", &config.pubmed_glob, "pubmed_keys"),
+        ("openalex", &config.openalex_glob, "openalex_keys"),
+        ("s2", &config.s2_glob, "s2_keys"),
+    ] {
+        if !table_exists(conn, table)? {
+            return Ok(false);
+        }
+        let current_hash = glob_content_hash(conn, glob)?;
+        if stored_hash(conn, source)?.as_deref() != Some(current_hash.as_str()) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Escapes single quotes so a path/glob can be embedded in a SQL string literal.
+fn quote_glob(glob: &str) -> String {
+    glob.replace('\'', "''")
+}
+
+/// The expression used to derive `pmid_norm` in each `*_keys` table.
+/// `strict_pmid` requires an exact string match; otherwise `normalize_pmid`
+/// trims whitespace, drops a trailing `.N` version suffix, and strips
+/// non-digits before comparing.
+fn pmid_norm_expr(strict_pmid: bool) -> &'static str {
+    if strict_pmid {
+        "CAST(pmid AS VARCHAR)"
+    } else {
+        "normalize_pmid(pmid)"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use duckdb::Connection;
+
+    #[test]
+    fn from_globs_joins_sources_sharing_one_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/AA' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'OA-1' AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, CAST(NULL AS BIGINT) AS pmid, 'S2-1' AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+
+        let mut steps = Vec::new();
+        run(&config, |step| steps.push(step.to_string())).unwrap();
+        assert_eq!(steps, ["pubmed_keys", "openalex_keys", "s2_keys", "join", "write"]);
+
+        let check = Connection::open_in_memory().unwrap();
+        let (pmid, openalex_id, s2_id): (i64, String, String) = check
+            .query_row(
+                &format!("SELECT pmid, openalex_id, s2_id FROM read_parquet('{dir_path}/joined.parquet')"),
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(pmid, 111);
+        assert_eq!(openalex_id, "OA-1");
+        assert_eq!(s2_id, "S2-1");
+    }
+
+    #[test]
+    fn extra_doi_normalization_strips_a_legacy_prefix_before_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT 'legacy:10.1/AA' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'OA-1' AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.extra_doi_normalization = Some("regexp_replace(d, '^legacy:', '')".to_string());
+
+        run(&config, |_| {}).unwrap();
+
+        let check = Connection::open_in_memory().unwrap();
+        let openalex_id: String = check
+            .query_row(
+                &format!("SELECT openalex_id FROM read_parquet('{dir_path}/joined.parquet') WHERE pmid = 111"),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(openalex_id, "OA-1", "the prefix strip must run before the DOIs are compared");
+    }
+
+    #[test]
+    fn extra_doi_normalization_rejects_invalid_sql_before_the_run_starts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = JoinConfig::from_globs(
+            "*.parquet".to_string(),
+            "*.parquet".to_string(),
+            "*.parquet".to_string(),
+            dir.path().to_path_buf(),
+        );
+        config.extra_doi_normalization = Some("not valid sql (((".to_string());
+
+        let err = run(&config, |_| {}).unwrap_err();
+        assert!(
+            err.to_string().contains("invalid extra_doi_normalization"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn coauthorship_export_produces_undirected_edges_for_one_paper() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'S2-1' AS s2_id,
+                              ['a1', 'a2', 'a3'] AS paper_authors)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.export_coauthorship = true;
+
+        let mut steps = Vec::new();
+        run(&config, |step| steps.push(step.to_string())).unwrap();
+        assert_eq!(
+            steps,
+            ["pubmed_keys", "openalex_keys", "s2_keys", "join", "write", "author_edges"]
+        );
+
+        let check = Connection::open_in_memory().unwrap();
+        let edge_count: i64 = check
+            .query_row(
+                &format!("SELECT count(*) FROM read_parquet('{dir_path}/author_edges.parquet')"),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(edge_count, 3);
+    }
+
+    #[test]
+    fn persist_db_skips_key_table_rebuild_on_unchanged_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/AA' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'OA-1' AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, CAST(NULL AS BIGINT) AS pmid, 'S2-1' AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.persist_db = Some(dir.path().join("join.duckdb"));
+
+        let mut first_steps = Vec::new();
+        run(&config, |step| first_steps.push(step.to_string())).unwrap();
+        assert_eq!(first_steps, ["pubmed_keys", "openalex_keys", "s2_keys", "join", "write"]);
+
+        let mut second_steps = Vec::new();
+        run(&config, |step| second_steps.push(step.to_string())).unwrap();
+        assert_eq!(second_steps, ["reuse_keys", "join", "write"]);
+    }
+
+    #[test]
+    fn pmid_fallback_matches_only_after_normalization() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT CAST(NULL AS VARCHAR) AS doi, '12345 ' AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, '12345' AS pmid, 'OA-1' AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS VARCHAR) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+
+        run(&config, |_| {}).unwrap();
+
+        let check = Connection::open_in_memory().unwrap();
+        let (row_count, openalex_id): (i64, String) = check
+            .query_row(
+                &format!(
+                    "SELECT count(*), any_value(openalex_id) FROM read_parquet('{dir_path}/joined.parquet')"
+                ),
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(row_count, 1, "the two rows should collapse into one match");
+        assert_eq!(openalex_id, "OA-1");
+    }
+
+    #[test]
+    fn strict_pmid_requires_an_exact_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT CAST(NULL AS VARCHAR) AS doi, '12345 ' AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, '12345' AS pmid, 'OA-1' AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS VARCHAR) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.strict_pmid = true;
+
+        run(&config, |_| {}).unwrap();
+
+        let check = Connection::open_in_memory().unwrap();
+        let row_count: i64 = check
+            .query_row(
+                &format!("SELECT count(*) FROM read_parquet('{dir_path}/joined.parquet')"),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(row_count, 2, "unnormalized whitespace should keep the rows apart");
+    }
+
+    #[test]
+    fn report_anomalies_flags_a_doi_shared_by_two_pmids() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES
+                     ('10.1/aa', 111, 'Title A'),
+                     ('10.1/aa', 222, 'Title A dup')) AS t(doi, pmid, title))
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.report_anomalies = true;
+
+        let mut steps = Vec::new();
+        run(&config, |step| steps.push(step.to_string())).unwrap();
+        assert_eq!(
+            steps,
+            ["pubmed_keys", "openalex_keys", "s2_keys", "join", "write", "anomalies"]
+        );
+
+        let check = Connection::open_in_memory().unwrap();
+        let (doi, pmid_count): (String, i64) = check
+            .query_row(
+                &format!("SELECT doi, pmid_count FROM read_parquet('{dir_path}/anomalies.parquet')"),
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(doi, "10.1/aa");
+        assert_eq!(pmid_count, 2);
+    }
+
+    #[test]
+    fn crosswalk_columns_land_on_matched_nodes_and_are_null_for_unmatched() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES
+                     ('10.1/aa', 111, 'Title A'),
+                     ('10.1/bb', 222, 'Title B')) AS t(doi, pmid, title))
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT 111 AS pmid, 'INST-1' AS internal_id)
+                     TO '{dir_path}/crosswalk.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.crosswalk = Some(dir.path().join("crosswalk.parquet"));
+
+        let mut steps = Vec::new();
+        run(&config, |step| steps.push(step.to_string())).unwrap();
+        assert_eq!(
+            steps,
+            ["pubmed_keys", "openalex_keys", "s2_keys", "join", "crosswalk", "write"]
+        );
+
+        let check = Connection::open_in_memory().unwrap();
+        let mut rows: Vec<(i64, Option<String>)> = check
+            .prepare(&format!(
+                "SELECT pmid, xw_internal_id FROM read_parquet('{dir_path}/joined.parquet') ORDER BY pmid"
+            ))
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<duckdb::Result<_>>()
+            .unwrap();
+        rows.sort();
+
+        assert_eq!(rows, vec![(111, Some("INST-1".to_string())), (222, None)]);
+    }
+
+    #[test]
+    fn crosswalk_without_a_pmid_column_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT 'INST-1' AS internal_id)
+                     TO '{dir_path}/crosswalk.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.crosswalk = Some(dir.path().join("crosswalk.parquet"));
+
+        assert!(run(&config, |_| {}).is_err());
+    }
+
+    #[test]
+    fn null_style_normalizes_pubmed_null_and_s2_empty_string_uniformly() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, CAST(NULL AS VARCHAR) AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, '' AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let read_joined = |style: NullStyle, out_dir_name: &str| -> (Option<String>, Option<String>) {
+            let out_dir = dir.path().join(out_dir_name);
+            let mut config = JoinConfig::from_globs(
+                format!("{dir_path}/pubmed_*.parquet"),
+                format!("{dir_path}/openalex_*.parquet"),
+                format!("{dir_path}/s2_*.parquet"),
+                out_dir.clone(),
+            );
+            config.null_style = style;
+            run(&config, |_| {}).unwrap();
+
+            let check = Connection::open_in_memory().unwrap();
+            check
+                .query_row(
+                    &format!(
+                        "SELECT pubmed_title, s2_id FROM read_parquet('{}')",
+                        out_dir.join("joined.parquet").display()
+                    ),
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap()
+        };
+
+        let (title, s2_id) = read_joined(NullStyle::EmptyAsNull, "out_empty_as_null");
+        assert_eq!(title, None, "pubmed's NULL title stays NULL");
+        assert_eq!(s2_id, None, "s2's empty-string id becomes NULL");
+
+        let (title, s2_id) = read_joined(NullStyle::NullAsEmpty, "out_null_as_empty");
+        assert_eq!(title, Some(String::new()), "pubmed's NULL title becomes empty");
+        assert_eq!(s2_id, Some(String::new()), "s2's empty-string id stays empty");
+    }
+
+    #[test]
+    fn run_with_progress_reports_increasing_fractions_across_pubmed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/bb' AS doi, 222 AS pmid, 'Title B' AS title)
+                     TO '{dir_path}/pubmed_1.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+
+        let mut pubmed_fractions = Vec::new();
+        run_with_progress(&config, |_step| {}, |step, fraction| {
+            if step == "pubmed_keys" {
+                pubmed_fractions.push(fraction);
+            }
+        })
+        .unwrap();
+
+        assert_eq!(pubmed_fractions, [0.5, 1.0]);
+    }
+
+    #[test]
+    fn summary_reports_a_nonempty_duckdb_version_and_the_configured_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'Title A' AS title)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.write_join_meta = true;
+
+        let summary = run(&config, |_| {}).unwrap();
+        assert!(!summary.duckdb_version.is_empty());
+        assert!(summary.effective_threads > 0);
+
+        let written = std::fs::read_to_string(dir.path().join("join_meta.json")).unwrap();
+        assert!(written.contains(&summary.duckdb_version));
+    }
+
+    #[test]
+    fn language_filter_keeps_a_normalized_match_and_drops_others() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES
+                     ('10.1/aa', 111, 'Title A', 'eng'),
+                     ('10.1/bb', 222, 'Title B', 'fre')) AS t(doi, pmid, title, language))
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.languages = Some(vec!["en".to_string()]);
+
+        run(&config, |_| {}).unwrap();
+
+        let check = Connection::open_in_memory().unwrap();
+        let pmids: Vec<i64> = check
+            .prepare(&format!("SELECT pmid FROM read_parquet('{dir_path}/joined.parquet') ORDER BY pmid"))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<duckdb::Result<_>>()
+            .unwrap();
+
+        assert_eq!(pmids, vec![111], "the eng/en node should survive and the fre node should be dropped");
+    }
+
+    #[test]
+    fn language_filter_is_a_no_op_when_neither_source_has_a_language_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        // Matches the real papeline-pubmed schema (crates/pubmed/src/transform.rs),
+        // which never emits a `language` column, and a synthetic OpenAlex
+        // fixture that likewise omits one.
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES
+                     ('10.1/aa', 111, 'Title A'),
+                     ('10.1/bb', 222, 'Title B')) AS t(doi, pmid, title))
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.languages = Some(vec!["en".to_string()]);
+
+        run(&config, |_| {}).unwrap();
+
+        let check = Connection::open_in_memory().unwrap();
+        let pmids: Vec<i64> = check
+            .prepare(&format!("SELECT pmid FROM read_parquet('{dir_path}/joined.parquet') ORDER BY pmid"))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<duckdb::Result<_>>()
+            .unwrap();
+
+        assert_eq!(
+            pmids,
+            vec![111, 222],
+            "with no language column on either source, the filter must not silently drop every row"
+        );
+    }
+
+    #[test]
+    fn pmcid_bridge_matches_a_node_that_doi_and_pmid_both_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT '10.1/aa' AS doi, 111 AS pmid, 'Title A' AS title, 'PMC12345' AS pmc_id)
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT '10.1/zz' AS doi, 999 AS pmid, 'OA-1' AS openalex_id, 'pmc12345' AS pmcid)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.match_pmcid = true;
+
+        let summary = run(&config, |_| {}).unwrap();
+        assert_eq!(summary.openalex_pmcid_matches, 1);
+
+        let check = Connection::open_in_memory().unwrap();
+        let openalex_id: String = check
+            .query_row(
+                &format!("SELECT openalex_id FROM read_parquet('{dir_path}/joined.parquet') WHERE pmid = 111"),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(openalex_id, "OA-1", "the PMCID-only match should be attributed to the pmcid pass");
+    }
+
+    #[test]
+    fn assign_node_ids_produces_a_dense_contiguous_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().display();
+
+        let setup = Connection::open_in_memory().unwrap();
+        setup
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES
+                     ('10.1/aa', 111, 'Title A'),
+                     ('10.1/bb', 222, 'Title B'),
+                     ('10.1/cc', 333, 'Title C')) AS t(doi, pmid, title))
+                     TO '{dir_path}/pubmed_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS openalex_id)
+                     TO '{dir_path}/openalex_0.parquet' (FORMAT PARQUET);
+                 COPY (SELECT CAST(NULL AS VARCHAR) AS doi, CAST(NULL AS BIGINT) AS pmid, CAST(NULL AS VARCHAR) AS s2_id)
+                     TO '{dir_path}/s2_0.parquet' (FORMAT PARQUET);"
+            ))
+            .unwrap();
+
+        let mut config = JoinConfig::from_globs(
+            format!("{dir_path}/pubmed_*.parquet"),
+            format!("{dir_path}/openalex_*.parquet"),
+            format!("{dir_path}/s2_*.parquet"),
+            dir.path().to_path_buf(),
+        );
+        config.assign_node_ids = true;
+        run(&config, |_| {}).unwrap();
+
+        let check = Connection::open_in_memory().unwrap();
+        let mut node_ids: Vec<i64> = check
+            .prepare(&format!(
+                "SELECT node_id FROM read_parquet('{}') ORDER BY node_id",
+                dir.path().join("joined.parquet").display()
+            ))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<duckdb::Result<_>>()
+            .unwrap();
+
+        node_ids.sort_unstable();
+        assert_eq!(node_ids, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+/// Configuration for a PubMed fetch/parse/transform run.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub baseline_dir: PathBuf,
+    pub updatefiles_dir: PathBuf,
+    pub output_dir: PathBuf,
+    pub batch_rows: usize,
+    pub zstd_level: i32,
+    /// When set, each emitted row carries a `source_file` column naming the
+    /// `.xml.gz` it was parsed from. Off by default since it adds a column
+    /// most consumers don't need.
+    pub record_provenance: bool,
+    /// When set, also emits an `authors` column encoding each article's
+    /// authors as a native `List<Struct<last_name, fore_name, initials,
+    /// orcid>>` instead of requiring consumers to parse `authors_json`. Off
+    /// by default; `authors_json` is always emitted either way.
+    pub authors_as_struct: bool,
+    /// When set, keeps only the highest-`version` record per PMID across all
+    /// parsed entries instead of emitting every version baseline/updatefiles
+    /// carry. Ties (equal version) keep whichever entry was processed last,
+    /// so passing updatefiles after the baseline prefers the updatefile. Off
+    /// by default, since it requires buffering every article in memory
+    /// instead of streaming shards as they fill.
+    pub dedupe_pmids: bool,
+    /// When set, only entries whose PubMed sequence number (e.g. `500` in
+    /// `pubmed24n0500.xml.gz`) falls in this inclusive range are kept, via
+    /// [`crate::entries::filter_by_range`]. Useful for backfills and tests
+    /// that only need a handful of files instead of a whole baseline. Off
+    /// by default.
+    pub file_range: Option<(u32, u32)>,
+    /// When set, `title`/`abstract_text` longer than this many bytes are
+    /// truncated on a char boundary to at most this many bytes, plus a
+    /// trailing 3-byte ellipsis, before being written, so a handful of
+    /// pathologically long abstracts can't bloat parquet or break a
+    /// downstream consumer with a fixed varchar limit.
+    /// Truncations are counted into [`crate::worker::WorkerStats::truncated`].
+    /// Off by default.
+    pub max_text_len: Option<usize>,
+    /// When set, updatefile entries modified after this date are excluded
+    /// via [`crate::entries::filter_by_update_cutoff`], so a run can ignore
+    /// very recent, possibly-incomplete updates. Baseline entries are never
+    /// affected. Off by default.
+    pub update_cutoff: Option<NaiveDate>,
+    /// When set, a version tie between a baseline and an updatefile record
+    /// for the same PMID always keeps the updatefile's copy, regardless of
+    /// which entry [`crate::worker::run_with_entries_deduped`] happened to
+    /// process last. Off by default, since ties already resolve to
+    /// whichever entry was processed last, which is the updatefile in the
+    /// conventional baseline-then-updates ordering.
+    pub prefer_updates: bool,
+}
+
+impl Config {
+    pub fn new(baseline_dir: PathBuf, updatefiles_dir: PathBuf, output_dir: PathBuf) -> Self {
+        Config {
+            baseline_dir,
+            updatefiles_dir,
+            output_dir,
+            batch_rows: 50_000,
+            zstd_level: 15,
+            record_provenance: false,
+            authors_as_struct: false,
+            dedupe_pmids: false,
+            file_range: None,
+            max_text_len: None,
+            update_cutoff: None,
+            prefer_updates: false,
+        }
+    }
+}
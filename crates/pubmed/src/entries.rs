@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use papeline_core::Result;
+
+/// One `.xml.gz` file to parse, tagged with which set it came from.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub kind: EntryKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Baseline,
+    Updatefile,
+}
+
+impl Entry {
+    /// Basename used as the `source_file` provenance value.
+    pub fn filename(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Lists `*.xml.gz` files under `dir`, sorted by name so baseline and
+/// updatefile runs are deterministic.
+pub fn list_entries(dir: &Path, kind: EntryKind) -> Result<Vec<Entry>> {
+    let pattern = dir.join("*.xml.gz");
+    let mut paths: Vec<PathBuf> = glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| papeline_core::Error::Other(e.to_string()))?
+        .filter_map(|r| r.ok())
+        .collect();
+    paths.sort();
+    Ok(paths.into_iter().map(|path| Entry { path, kind }).collect())
+}
+
+/// Extracts the sequence number from a name like `pubmed24n0500.xml.gz`
+/// (the run of digits immediately before the `.xml.gz` extension).
+fn sequence_number(entry: &Entry) -> Option<u32> {
+    let stem = entry.filename().strip_suffix(".xml.gz")?.to_string();
+    let digits: String = stem.chars().rev().take_while(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().rev().collect::<String>().parse().ok()
+}
+
+/// Keeps only entries whose sequence number falls within `range`
+/// (inclusive), for callers that want a sub-range of a baseline instead of
+/// everything under a directory. Errors if nothing matches, since that
+/// likely means `range` doesn't line up with this manifest's numbering.
+pub fn filter_by_range(entries: Vec<Entry>, range: (u32, u32)) -> Result<Vec<Entry>> {
+    let (start, end) = range;
+    let filtered: Vec<Entry> = entries
+        .into_iter()
+        .filter(|entry| matches!(sequence_number(entry), Some(seq) if seq >= start && seq <= end))
+        .collect();
+    if filtered.is_empty() {
+        return Err(papeline_core::Error::Other(format!(
+            "file_range {start}-{end} matched no entries in the manifest"
+        )));
+    }
+    Ok(filtered)
+}
+
+/// Drops updatefile entries modified after `cutoff`, so a run can exclude
+/// very recent, possibly-incomplete updates. Baseline entries always pass
+/// through untouched, since there's only ever one baseline per year and
+/// excluding part of it isn't a meaningful operation. There's no per-file
+/// publication date in a PubMed manifest, so this uses the file's own
+/// modification time as a proxy — accurate as long as updatefiles are kept
+/// on disk with their original mtimes.
+pub fn filter_by_update_cutoff(entries: Vec<Entry>, cutoff: NaiveDate) -> Result<Vec<Entry>> {
+    entries
+        .into_iter()
+        .filter_map(|entry| match entry.kind {
+            EntryKind::Baseline => Some(Ok(entry)),
+            EntryKind::Updatefile => match modified_date(&entry.path) {
+                Ok(date) if date <= cutoff => Some(Ok(entry)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            },
+        })
+        .collect()
+}
+
+fn modified_date(path: &Path) -> Result<NaiveDate> {
+    let modified = std::fs::metadata(path)?.modified()?;
+    Ok(DateTime::<Utc>::from(modified).date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_by_range_keeps_only_the_sub_range() {
+        let dir = tempfile::tempdir().unwrap();
+        for seq in 1..=5 {
+            std::fs::write(dir.path().join(format!("pubmed24n{seq:04}.xml.gz")), b"").unwrap();
+        }
+
+        let entries = list_entries(dir.path(), EntryKind::Baseline).unwrap();
+        assert_eq!(entries.len(), 5);
+
+        let ranged = filter_by_range(entries, (2, 4)).unwrap();
+        let names: Vec<String> = ranged.iter().map(Entry::filename).collect();
+        assert_eq!(
+            names,
+            vec!["pubmed24n0002.xml.gz", "pubmed24n0003.xml.gz", "pubmed24n0004.xml.gz"]
+        );
+    }
+
+    #[test]
+    fn filter_by_update_cutoff_excludes_only_recent_updatefiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline_path = dir.path().join("pubmed24n0001.xml.gz");
+        let update_path = dir.path().join("pubmed24n0001.xml.gz.upd");
+        std::fs::write(&baseline_path, b"").unwrap();
+        std::fs::write(&update_path, b"").unwrap();
+
+        let entries = vec![
+            Entry {
+                path: baseline_path,
+                kind: EntryKind::Baseline,
+            },
+            Entry {
+                path: update_path,
+                kind: EntryKind::Updatefile,
+            },
+        ];
+
+        let far_future = NaiveDate::from_ymd_opt(2100, 1, 1).unwrap();
+        let kept = filter_by_update_cutoff(entries.clone(), far_future).unwrap();
+        assert_eq!(kept.len(), 2, "a cutoff after every mtime keeps everything");
+
+        let long_past = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let kept = filter_by_update_cutoff(entries, long_past).unwrap();
+        assert_eq!(kept.len(), 1, "the updatefile's mtime is after the cutoff and is dropped");
+        assert_eq!(kept[0].kind, EntryKind::Baseline, "baseline entries are never dropped by a cutoff");
+    }
+
+    #[test]
+    fn filter_by_range_errors_when_nothing_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pubmed24n0001.xml.gz"), b"").unwrap();
+
+        let entries = list_entries(dir.path(), EntryKind::Baseline).unwrap();
+        assert!(filter_by_range(entries, (500, 520)).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+//! Fetch, parse, and transform PubMed baseline/updatefile XML into parquet.
+
+pub mod config;
+pub mod entries;
+pub mod model;
+pub mod parse;
+pub mod transform;
+pub mod worker;
+
+pub use config::Config;
+pub use model::Article;
@@ -0,0 +1,63 @@
+/// A single `PubmedArticle` flattened into the columns we persist.
+///
+/// Fields map directly onto the subset of MEDLINE/PubMed XML tags the
+/// parser extracts; see [`crate::parse`] for how each one is populated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Article {
+    pub pmid: u32,
+    pub version: u32,
+    pub title: String,
+    pub abstract_text: Option<String>,
+    /// Authors serialized as a JSON array of `{last_name, fore_name}`
+    /// objects, derived from `authors`; `None` when the article has no
+    /// authors. Populated whether or not the native struct-list encoding of
+    /// `authors` is emitted as a column, which happens only when
+    /// [`crate::config::Config::authors_as_struct`] is set.
+    pub authors_json: Option<String>,
+    /// Structured per-author detail, parsed once and used to build both
+    /// `authors_json` and (when enabled) the `authors` struct-list column.
+    pub authors: Vec<Author>,
+    pub journal_title: Option<String>,
+    pub pub_year: Option<i32>,
+    /// Basename of the `.xml.gz` file this article was parsed from, present
+    /// only when [`crate::config::Config::record_provenance`] is set.
+    pub source_file: Option<String>,
+    /// Free-text `<GeneralNote>` elements, rarely present.
+    pub general_notes: Vec<String>,
+    /// Free-text `<SpaceFlightMission>` elements, rarely present.
+    pub space_flight_missions: Vec<String>,
+    /// `<OtherID Source="...">` values as `(source, value)` pairs, e.g. an
+    /// NLM unique identifier. Serialized to the `other_ids_json` column.
+    pub other_ids: Vec<(String, String)>,
+    /// `<OtherAbstract Language="...">` text as `(language, text)` pairs —
+    /// non-English abstracts PubMed carries alongside the primary one.
+    /// Serialized to the `other_abstracts_json` column.
+    pub other_abstracts: Vec<(String, String)>,
+    /// Derived from `<PublicationType>Retracted Publication</PublicationType>`
+    /// (or `Retraction of Publication`) and from a
+    /// `<CommentsCorrections RefType="RetractionIn">` link, so consumers get
+    /// a plain boolean instead of having to parse either themselves.
+    pub is_retracted: bool,
+    /// Derived from `<PublicationStatus>aheadofprint</PublicationStatus>` or
+    /// an `<Article PubModel="Electronic-eCollection">` attribute — both
+    /// mean the record lacks final pagination/dates, so consumers doing
+    /// longitudinal analysis can filter or flag it without parsing either
+    /// themselves.
+    pub is_ahead_of_print: bool,
+    /// `<CitationSubset>` values, e.g. `"IM"` for Index Medicus — used to
+    /// restrict a downstream analysis to core clinical journals.
+    pub citation_subsets: Vec<String>,
+    /// `<MedlineJournalInfo><Country>`: the journal's country of
+    /// publication, e.g. `"United States"`.
+    pub journal_country: Option<String>,
+}
+
+/// One `<Author>` entry on an article.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Author {
+    pub last_name: Option<String>,
+    pub fore_name: Option<String>,
+    pub initials: Option<String>,
+    /// From `<Identifier Source="ORCID">`, when present.
+    pub orcid: Option<String>,
+}
@@ -0,0 +1,433 @@
+use std::io::BufRead;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::model::{Article, Author};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("xml error: {0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("malformed PMID: {0}")]
+    BadPmid(String),
+}
+
+/// Parses every `PubmedArticle` out of a MEDLINE XML stream.
+///
+/// This only extracts the columns listed on [`Article`]; unrecognized tags
+/// are skipped rather than erroring, since the baseline and updatefile DTDs
+/// carry a long tail of optional elements we don't persist yet.
+pub fn parse_articles<R: BufRead>(reader: R) -> Result<Vec<Article>, ParseError> {
+    let mut xml = Reader::from_reader(reader);
+    xml.config_mut().trim_text(true);
+
+    let mut articles = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut authors: Vec<Author> = Vec::new();
+
+    let mut pmid: Option<u32> = None;
+    let mut version: u32 = 1;
+    let mut title = String::new();
+    let mut abstract_text: Option<String> = None;
+    let mut journal_title: Option<String> = None;
+    let mut pub_year: Option<i32> = None;
+    let mut cur_last_name: Option<String> = None;
+    let mut cur_fore_name: Option<String> = None;
+    let mut cur_initials: Option<String> = None;
+    let mut cur_orcid: Option<String> = None;
+    let mut cur_identifier_is_orcid = false;
+    let mut general_notes: Vec<String> = Vec::new();
+    let mut space_flight_missions: Vec<String> = Vec::new();
+    let mut other_ids: Vec<(String, String)> = Vec::new();
+    let mut other_abstracts: Vec<(String, String)> = Vec::new();
+    let mut cur_other_id_source: Option<String> = None;
+    let mut cur_other_id_value = String::new();
+    let mut cur_other_abstract_language: Option<String> = None;
+    let mut cur_other_abstract_text = String::new();
+    let mut publication_types: Vec<String> = Vec::new();
+    let mut has_retraction_in = false;
+    let mut publication_status: Option<String> = None;
+    let mut pub_model: Option<String> = None;
+    let mut citation_subsets: Vec<String> = Vec::new();
+    let mut journal_country: Option<String> = None;
+
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event_into(&mut buf)? {
+            Event::Eof => break,
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if name == "PubmedArticle" {
+                    pmid = None;
+                    version = 1;
+                    title.clear();
+                    abstract_text = None;
+                    journal_title = None;
+                    pub_year = None;
+                    authors.clear();
+                    general_notes.clear();
+                    space_flight_missions.clear();
+                    other_ids.clear();
+                    other_abstracts.clear();
+                    publication_types.clear();
+                    has_retraction_in = false;
+                    publication_status = None;
+                    pub_model = None;
+                    citation_subsets.clear();
+                    journal_country = None;
+                } else if name == "Author" {
+                    cur_last_name = None;
+                    cur_fore_name = None;
+                    cur_initials = None;
+                    cur_orcid = None;
+                } else if name == "Identifier" {
+                    cur_identifier_is_orcid = false;
+                } else if name == "OtherID" {
+                    cur_other_id_source = None;
+                    cur_other_id_value.clear();
+                } else if name == "OtherAbstract" {
+                    cur_other_abstract_language = None;
+                    cur_other_abstract_text.clear();
+                }
+                for attr in e.attributes().flatten() {
+                    if name == "PMID" && attr.key.as_ref() == b"Version" {
+                        version = String::from_utf8_lossy(&attr.value)
+                            .parse()
+                            .unwrap_or(1);
+                    } else if name == "Identifier"
+                        && attr.key.as_ref() == b"Source"
+                        && attr.value.as_ref() == b"ORCID"
+                    {
+                        cur_identifier_is_orcid = true;
+                    } else if name == "OtherID" && attr.key.as_ref() == b"Source" {
+                        cur_other_id_source =
+                            Some(String::from_utf8_lossy(&attr.value).into_owned());
+                    } else if name == "OtherAbstract" && attr.key.as_ref() == b"Language" {
+                        cur_other_abstract_language =
+                            Some(String::from_utf8_lossy(&attr.value).into_owned());
+                    } else if name == "CommentsCorrections"
+                        && attr.key.as_ref() == b"RefType"
+                        && attr.value.as_ref() == b"RetractionIn"
+                    {
+                        has_retraction_in = true;
+                    } else if name == "Article" && attr.key.as_ref() == b"PubModel" {
+                        pub_model = Some(String::from_utf8_lossy(&attr.value).into_owned());
+                    }
+                }
+                stack.push(name);
+            }
+            Event::Text(t) => {
+                let decoded = t.decode().map_err(quick_xml::Error::from)?;
+                let text = quick_xml::escape::unescape(&decoded)
+                    .map(|c| c.into_owned())
+                    .unwrap_or_else(|_| decoded.into_owned());
+                match stack.last().map(String::as_str) {
+                    Some("PMID") => {
+                        pmid = Some(
+                            text.trim()
+                                .parse()
+                                .map_err(|_| ParseError::BadPmid(text.clone()))?,
+                        );
+                    }
+                    Some("ArticleTitle") => title.push_str(&text),
+                    Some("AbstractText") if stack.iter().any(|s| s == "OtherAbstract") => {
+                        if !cur_other_abstract_text.is_empty() {
+                            cur_other_abstract_text.push(' ');
+                        }
+                        cur_other_abstract_text.push_str(&text);
+                    }
+                    Some("AbstractText") => {
+                        let entry = abstract_text.get_or_insert_with(String::new);
+                        if !entry.is_empty() {
+                            entry.push(' ');
+                        }
+                        entry.push_str(&text);
+                    }
+                    Some("OtherID") => cur_other_id_value.push_str(&text),
+                    Some("Title") if stack.iter().any(|s| s == "Journal") => {
+                        journal_title = Some(text);
+                    }
+                    Some("Year") if stack.iter().any(|s| s == "PubDate") => {
+                        pub_year = text.trim().parse().ok();
+                    }
+                    Some("LastName") if stack.iter().any(|s| s == "Author") => {
+                        cur_last_name = Some(text);
+                    }
+                    Some("ForeName") if stack.iter().any(|s| s == "Author") => {
+                        cur_fore_name = Some(text);
+                    }
+                    Some("Initials") if stack.iter().any(|s| s == "Author") => {
+                        cur_initials = Some(text);
+                    }
+                    Some("Identifier") if cur_identifier_is_orcid => {
+                        cur_orcid = Some(text);
+                    }
+                    Some("GeneralNote") => general_notes.push(text),
+                    Some("SpaceFlightMission") => space_flight_missions.push(text),
+                    Some("PublicationType") => publication_types.push(text),
+                    Some("PublicationStatus") => publication_status = Some(text),
+                    Some("CitationSubset") => citation_subsets.push(text),
+                    Some("Country") if stack.iter().any(|s| s == "MedlineJournalInfo") => {
+                        journal_country = Some(text);
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if name == "Author" {
+                    authors.push(Author {
+                        last_name: cur_last_name.take(),
+                        fore_name: cur_fore_name.take(),
+                        initials: cur_initials.take(),
+                        orcid: cur_orcid.take(),
+                    });
+                }
+                if name == "OtherID" {
+                    other_ids.push((
+                        cur_other_id_source.take().unwrap_or_default(),
+                        std::mem::take(&mut cur_other_id_value),
+                    ));
+                }
+                if name == "OtherAbstract" {
+                    other_abstracts.push((
+                        cur_other_abstract_language.take().unwrap_or_default(),
+                        std::mem::take(&mut cur_other_abstract_text),
+                    ));
+                }
+                if name == "PubmedArticle"
+                    && let Some(pmid) = pmid
+                {
+                    let authors_json = if authors.is_empty() {
+                        None
+                    } else {
+                        Some(encode_authors(&authors))
+                    };
+                    let is_retracted = has_retraction_in
+                        || publication_types
+                            .iter()
+                            .any(|t| t == "Retracted Publication" || t == "Retraction of Publication");
+                    let is_ahead_of_print = publication_status.as_deref() == Some("aheadofprint")
+                        || pub_model.as_deref() == Some("Electronic-eCollection");
+                    articles.push(Article {
+                        pmid,
+                        version,
+                        title: title.clone(),
+                        abstract_text: abstract_text.clone(),
+                        authors_json,
+                        authors: authors.clone(),
+                        journal_title: journal_title.clone(),
+                        pub_year,
+                        source_file: None,
+                        general_notes: general_notes.clone(),
+                        space_flight_missions: space_flight_missions.clone(),
+                        other_ids: other_ids.clone(),
+                        other_abstracts: other_abstracts.clone(),
+                        is_retracted,
+                        is_ahead_of_print,
+                        citation_subsets: citation_subsets.clone(),
+                        journal_country: journal_country.clone(),
+                    });
+                }
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(articles)
+}
+
+fn encode_authors(authors: &[Author]) -> String {
+    let parts: Vec<String> = authors
+        .iter()
+        .map(|author| {
+            format!(
+                "{{\"last_name\":{},\"fore_name\":{}}}",
+                json_opt_string(author.last_name.as_deref()),
+                json_opt_string(author.fore_name.as_deref()),
+            )
+        })
+        .collect();
+    format!("[{}]", parts.join(","))
+}
+
+fn json_opt_string(value: Option<&str>) -> String {
+    match value {
+        Some(v) => format!("{:?}", v),
+        None => "null".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_general_notes_and_space_flight_missions() {
+        let xml = "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Title</ArticleTitle></Article>\
+             <GeneralNote>Funded by NASA grant 123</GeneralNote>\
+             <SpaceFlightMission>STS-1</SpaceFlightMission>\
+             </MedlineCitation></PubmedArticle></PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].general_notes, vec!["Funded by NASA grant 123"]);
+        assert_eq!(articles[0].space_flight_missions, vec!["STS-1"]);
+    }
+
+    #[test]
+    fn parses_author_initials_and_orcid() {
+        let xml = "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Title</ArticleTitle>\
+             <AuthorList><Author>\
+             <LastName>Doe</LastName><ForeName>Jane</ForeName><Initials>J</Initials>\
+             <Identifier Source=\"ORCID\">0000-0001-2345-6789</Identifier>\
+             </Author></AuthorList></Article>\
+             </MedlineCitation></PubmedArticle></PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(
+            articles[0].authors,
+            vec![Author {
+                last_name: Some("Doe".to_string()),
+                fore_name: Some("Jane".to_string()),
+                initials: Some("J".to_string()),
+                orcid: Some("0000-0001-2345-6789".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_other_ids_and_other_abstracts() {
+        let xml = "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Title</ArticleTitle>\
+             <Abstract><AbstractText>English abstract.</AbstractText></Abstract>\
+             </Article>\
+             <OtherID Source=\"NLM\">9876543</OtherID>\
+             <OtherAbstract Language=\"fre\">\
+             <AbstractText>Résumé en français.</AbstractText>\
+             </OtherAbstract>\
+             </MedlineCitation></PubmedArticle></PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(
+            articles[0].abstract_text.as_deref(),
+            Some("English abstract.")
+        );
+        assert_eq!(
+            articles[0].other_ids,
+            vec![("NLM".to_string(), "9876543".to_string())]
+        );
+        assert_eq!(
+            articles[0].other_abstracts,
+            vec![("fre".to_string(), "Résumé en français.".to_string())]
+        );
+    }
+
+    #[test]
+    fn is_retracted_true_via_publication_type_and_false_for_a_plain_article() {
+        let xml = "<PubmedArticleSet>\
+             <PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Retracted Title</ArticleTitle>\
+             <PublicationTypeList>\
+             <PublicationType>Journal Article</PublicationType>\
+             <PublicationType>Retracted Publication</PublicationType>\
+             </PublicationTypeList>\
+             </Article>\
+             </MedlineCitation></PubmedArticle>\
+             <PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">2</PMID>\
+             <Article><ArticleTitle>Plain Title</ArticleTitle>\
+             <PublicationTypeList>\
+             <PublicationType>Journal Article</PublicationType>\
+             </PublicationTypeList>\
+             </Article>\
+             </MedlineCitation></PubmedArticle>\
+             </PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert!(articles[0].is_retracted);
+        assert!(!articles[1].is_retracted);
+    }
+
+    #[test]
+    fn is_retracted_true_via_comments_corrections_retraction_link() {
+        let xml = "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Title</ArticleTitle></Article>\
+             <CommentsCorrectionsList>\
+             <CommentsCorrections RefType=\"RetractionIn\">\
+             <RefSource>J Foo. 2020</RefSource>\
+             </CommentsCorrections>\
+             </CommentsCorrectionsList>\
+             </MedlineCitation></PubmedArticle></PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert!(articles[0].is_retracted);
+    }
+
+    #[test]
+    fn is_ahead_of_print_true_for_aheadofprint_status_and_false_for_ppublish() {
+        let xml = "<PubmedArticleSet>\
+             <PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Ahead Title</ArticleTitle></Article>\
+             </MedlineCitation>\
+             <PubmedData><PublicationStatus>aheadofprint</PublicationStatus></PubmedData>\
+             </PubmedArticle>\
+             <PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">2</PMID>\
+             <Article><ArticleTitle>Print Title</ArticleTitle></Article>\
+             </MedlineCitation>\
+             <PubmedData><PublicationStatus>ppublish</PublicationStatus></PubmedData>\
+             </PubmedArticle>\
+             </PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert!(articles[0].is_ahead_of_print);
+        assert!(!articles[1].is_ahead_of_print);
+    }
+
+    #[test]
+    fn is_ahead_of_print_true_for_electronic_ecollection_pub_model() {
+        let xml = "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article PubModel=\"Electronic-eCollection\">\
+             <ArticleTitle>Online Only Title</ArticleTitle></Article>\
+             </MedlineCitation>\
+             <PubmedData><PublicationStatus>epublish</PublicationStatus></PubmedData>\
+             </PubmedArticle></PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert!(articles[0].is_ahead_of_print);
+    }
+
+    #[test]
+    fn parses_citation_subsets_and_journal_country() {
+        let xml = "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"1\">1</PMID>\
+             <Article><ArticleTitle>Title</ArticleTitle></Article>\
+             <MedlineJournalInfo><Country>United States</Country></MedlineJournalInfo>\
+             <CitationSubset>IM</CitationSubset>\
+             </MedlineCitation></PubmedArticle></PubmedArticleSet>";
+
+        let articles = parse_articles(xml.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].citation_subsets, vec!["IM".to_string()]);
+        assert_eq!(articles[0].journal_country.as_deref(), Some("United States"));
+    }
+}
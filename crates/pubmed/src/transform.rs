@@ -0,0 +1,428 @@
+use std::sync::Arc;
+
+use arrow::array::{
+    BooleanArray, Int32Array, ListArray, ListBuilder, StringArray, StringBuilder, StructBuilder,
+    UInt32Array,
+};
+use arrow::datatypes::{DataType, Field, Fields, Schema};
+use arrow::record_batch::RecordBatch;
+use papeline_core::Result;
+
+use crate::model::Article;
+
+/// Buffers parsed [`Article`] rows and converts finished batches to Arrow.
+///
+/// Wraps [`papeline_core::accumulator::Accumulator`] rather than using it
+/// directly so the PubMed worker gets a `push` that knows about
+/// provenance tagging without every caller re-deriving the schema.
+pub struct ArticleAccumulator {
+    inner: papeline_core::accumulator::Accumulator<Article>,
+    record_provenance: bool,
+    authors_as_struct: bool,
+    max_text_len: Option<usize>,
+    truncated: usize,
+}
+
+impl ArticleAccumulator {
+    pub fn new(
+        batch_rows: usize,
+        record_provenance: bool,
+        authors_as_struct: bool,
+        max_text_len: Option<usize>,
+    ) -> Self {
+        ArticleAccumulator {
+            inner: papeline_core::accumulator::Accumulator::new(batch_rows),
+            record_provenance,
+            authors_as_struct,
+            max_text_len,
+            truncated: 0,
+        }
+    }
+
+    /// Appends `article`, stamping `source_file` onto it first when
+    /// provenance tracking is enabled and truncating `title`/`abstract_text`
+    /// when `max_text_len` is set. Returns `true` if the caller should flush
+    /// the accumulated batch now.
+    pub fn push(&mut self, mut article: Article, source_file: &str) -> bool {
+        if self.record_provenance {
+            article.source_file = Some(source_file.to_string());
+        }
+        if let Some(max_len) = self.max_text_len {
+            if truncate_text(&mut article.title, max_len) {
+                self.truncated += 1;
+            }
+            if let Some(abstract_text) = &mut article.abstract_text
+                && truncate_text(abstract_text, max_len)
+            {
+                self.truncated += 1;
+            }
+        }
+        self.inner.push(article)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Number of `title`/`abstract_text` values truncated so far, counted
+    /// per field (an article truncated on both counts twice).
+    pub fn truncated(&self) -> usize {
+        self.truncated
+    }
+
+    pub fn schema(&self) -> Arc<Schema> {
+        schema(self.record_provenance, self.authors_as_struct)
+    }
+
+    /// Drains the buffered rows into a single `RecordBatch`.
+    pub fn take_batch(&mut self) -> Result<RecordBatch> {
+        let rows = self.inner.take();
+        to_record_batch(&rows, self.record_provenance, self.authors_as_struct)
+    }
+}
+
+/// Truncates `s` in place to at most `max_len` bytes, on a char boundary,
+/// then appends an ellipsis (3 more bytes), so a truncated value is at most
+/// `max_len + 3` bytes. Returns whether truncation happened; a `s` already
+/// within `max_len` is left untouched.
+fn truncate_text(s: &mut String, max_len: usize) -> bool {
+    if s.len() <= max_len {
+        return false;
+    }
+    let mut boundary = max_len;
+    while boundary > 0 && !s.is_char_boundary(boundary) {
+        boundary -= 1;
+    }
+    s.truncate(boundary);
+    s.push('…');
+    true
+}
+
+/// Fields of the `authors` struct-list entry: `last_name`, `fore_name`,
+/// `initials`, `orcid`, all nullable `Utf8`.
+fn author_struct_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("last_name", DataType::Utf8, true),
+        Field::new("fore_name", DataType::Utf8, true),
+        Field::new("initials", DataType::Utf8, true),
+        Field::new("orcid", DataType::Utf8, true),
+    ])
+}
+
+fn schema(record_provenance: bool, authors_as_struct: bool) -> Arc<Schema> {
+    let mut fields = vec![
+        Field::new("pmid", DataType::UInt32, false),
+        Field::new("version", DataType::UInt32, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("abstract_text", DataType::Utf8, true),
+        Field::new("authors_json", DataType::Utf8, true),
+        Field::new("journal_title", DataType::Utf8, true),
+        Field::new("pub_year", DataType::Int32, true),
+        Field::new(
+            "general_notes",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            true,
+        ),
+        Field::new(
+            "space_flight_missions",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            true,
+        ),
+        Field::new("other_ids_json", DataType::Utf8, true),
+        Field::new("other_abstracts_json", DataType::Utf8, true),
+        Field::new("is_retracted", DataType::Boolean, false),
+        Field::new("is_ahead_of_print", DataType::Boolean, false),
+        Field::new(
+            "citation_subsets",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            true,
+        ),
+        Field::new("journal_country", DataType::Utf8, true),
+    ];
+    if authors_as_struct {
+        fields.push(Field::new(
+            "authors",
+            DataType::List(Arc::new(Field::new(
+                "item",
+                DataType::Struct(author_struct_fields()),
+                true,
+            ))),
+            true,
+        ));
+    }
+    if record_provenance {
+        fields.push(Field::new("source_file", DataType::Utf8, true));
+    }
+    Arc::new(Schema::new(fields))
+}
+
+fn to_record_batch(rows: &[Article], record_provenance: bool, authors_as_struct: bool) -> Result<RecordBatch> {
+    let pmid: UInt32Array = rows.iter().map(|a| a.pmid).collect();
+    let version: UInt32Array = rows.iter().map(|a| a.version).collect();
+    let title: StringArray = rows.iter().map(|a| Some(a.title.as_str())).collect();
+    let abstract_text: StringArray = rows.iter().map(|a| a.abstract_text.as_deref()).collect();
+    let authors_json: StringArray = rows.iter().map(|a| a.authors_json.as_deref()).collect();
+    let journal_title: StringArray = rows.iter().map(|a| a.journal_title.as_deref()).collect();
+    let pub_year: Int32Array = rows.iter().map(|a| a.pub_year).collect();
+    let general_notes = build_string_list(rows, |a| &a.general_notes);
+    let space_flight_missions = build_string_list(rows, |a| &a.space_flight_missions);
+    let other_ids_json: StringArray = rows
+        .iter()
+        .map(|a| encode_pairs(&a.other_ids, "source", "value"))
+        .collect();
+    let other_abstracts_json: StringArray = rows
+        .iter()
+        .map(|a| encode_pairs(&a.other_abstracts, "language", "text"))
+        .collect();
+    let is_retracted: BooleanArray = rows.iter().map(|a| Some(a.is_retracted)).collect();
+    let is_ahead_of_print: BooleanArray = rows.iter().map(|a| Some(a.is_ahead_of_print)).collect();
+    let citation_subsets = build_string_list(rows, |a| &a.citation_subsets);
+    let journal_country: StringArray = rows.iter().map(|a| a.journal_country.as_deref()).collect();
+
+    let mut columns: Vec<Arc<dyn arrow::array::Array>> = vec![
+        Arc::new(pmid),
+        Arc::new(version),
+        Arc::new(title),
+        Arc::new(abstract_text),
+        Arc::new(authors_json),
+        Arc::new(journal_title),
+        Arc::new(pub_year),
+        Arc::new(general_notes),
+        Arc::new(space_flight_missions),
+        Arc::new(other_ids_json),
+        Arc::new(other_abstracts_json),
+        Arc::new(is_retracted),
+        Arc::new(is_ahead_of_print),
+        Arc::new(citation_subsets),
+        Arc::new(journal_country),
+    ];
+    if authors_as_struct {
+        columns.push(Arc::new(build_author_struct_list(rows)));
+    }
+    if record_provenance {
+        let source_file: StringArray = rows.iter().map(|a| a.source_file.as_deref()).collect();
+        columns.push(Arc::new(source_file));
+    }
+
+    Ok(RecordBatch::try_new(schema(record_provenance, authors_as_struct), columns)?)
+}
+
+/// Builds a `List<Utf8>` column, emitting null (rather than an empty list)
+/// for rows where `values` returns nothing — these fields are rare, so most
+/// rows should round-trip as null.
+fn build_string_list(rows: &[Article], values: impl Fn(&Article) -> &[String]) -> ListArray {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for row in rows {
+        let items = values(row);
+        if items.is_empty() {
+            builder.append_null();
+        } else {
+            for item in items {
+                builder.values().append_value(item);
+            }
+            builder.append(true);
+        }
+    }
+    builder.finish()
+}
+
+/// Encodes `pairs` (e.g. `other_ids`/`other_abstracts`) as a JSON array of
+/// `{key_name, value_name}` objects, or `None` when `pairs` is empty, so most
+/// rows round-trip as null the way the other rarely-present fields do.
+fn encode_pairs(pairs: &[(String, String)], key_name: &str, value_name: &str) -> Option<String> {
+    if pairs.is_empty() {
+        return None;
+    }
+    let parts: Vec<String> = pairs
+        .iter()
+        .map(|(key, value)| format!("{{{:?}:{:?},{:?}:{:?}}}", key_name, key, value_name, value))
+        .collect();
+    Some(format!("[{}]", parts.join(",")))
+}
+
+/// Builds the `authors` `List<Struct<last_name, fore_name, initials, orcid>>`
+/// column, the native encoding consumers get alongside `authors_json`
+/// (which is always emitted) when [`crate::config::Config::authors_as_struct`]
+/// is set.
+fn build_author_struct_list(rows: &[Article]) -> ListArray {
+    let struct_builder = StructBuilder::from_fields(author_struct_fields(), 0);
+    let mut builder = ListBuilder::new(struct_builder);
+    for row in rows {
+        if row.authors.is_empty() {
+            builder.append_null();
+            continue;
+        }
+        let struct_builder = builder.values();
+        for author in &row.authors {
+            struct_builder
+                .field_builder::<StringBuilder>(0)
+                .unwrap()
+                .append_option(author.last_name.as_deref());
+            struct_builder
+                .field_builder::<StringBuilder>(1)
+                .unwrap()
+                .append_option(author.fore_name.as_deref());
+            struct_builder
+                .field_builder::<StringBuilder>(2)
+                .unwrap()
+                .append_option(author.initials.as_deref());
+            struct_builder
+                .field_builder::<StringBuilder>(3)
+                .unwrap()
+                .append_option(author.orcid.as_deref());
+            struct_builder.append(true);
+        }
+        builder.append(true);
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, AsArray};
+
+    use super::*;
+    use crate::model::Author;
+
+    fn two_author_article() -> Article {
+        Article {
+            pmid: 1,
+            version: 1,
+            title: "Title".to_string(),
+            abstract_text: None,
+            authors_json: Some("[]".to_string()),
+            authors: vec![
+                Author {
+                    last_name: Some("Doe".to_string()),
+                    fore_name: Some("Jane".to_string()),
+                    initials: Some("J".to_string()),
+                    orcid: Some("0000-0001-2345-6789".to_string()),
+                },
+                Author {
+                    last_name: Some("Smith".to_string()),
+                    fore_name: Some("John".to_string()),
+                    initials: None,
+                    orcid: None,
+                },
+            ],
+            journal_title: None,
+            pub_year: None,
+            source_file: None,
+            general_notes: Vec::new(),
+            space_flight_missions: Vec::new(),
+            other_ids: Vec::new(),
+            other_abstracts: Vec::new(),
+            is_retracted: false,
+            is_ahead_of_print: false,
+            citation_subsets: Vec::new(),
+            journal_country: None,
+        }
+    }
+
+    #[test]
+    fn authors_as_struct_emits_nested_author_values() {
+        let rows = vec![two_author_article()];
+        let batch = to_record_batch(&rows, false, true).unwrap();
+
+        let authors = batch.column_by_name("authors").unwrap().as_list::<i32>();
+        assert_eq!(authors.len(), 1);
+        let entry = authors.value(0);
+        let structs = entry.as_struct();
+        assert_eq!(structs.len(), 2);
+
+        let last_names = structs.column(0).as_string::<i32>();
+        let fore_names = structs.column(1).as_string::<i32>();
+        let initials = structs.column(2).as_string::<i32>();
+        let orcids = structs.column(3).as_string::<i32>();
+
+        assert_eq!(last_names.value(0), "Doe");
+        assert_eq!(fore_names.value(0), "Jane");
+        assert_eq!(initials.value(0), "J");
+        assert_eq!(orcids.value(0), "0000-0001-2345-6789");
+
+        assert_eq!(last_names.value(1), "Smith");
+        assert_eq!(fore_names.value(1), "John");
+        assert!(initials.is_null(1));
+        assert!(orcids.is_null(1));
+    }
+
+    #[test]
+    fn authors_as_struct_off_by_default_omits_the_column() {
+        let rows = vec![two_author_article()];
+        let batch = to_record_batch(&rows, false, false).unwrap();
+        assert!(batch.column_by_name("authors").is_none());
+        assert!(batch.column_by_name("authors_json").is_some());
+    }
+
+    #[test]
+    fn is_retracted_column_reflects_the_article_field() {
+        let mut retracted = two_author_article();
+        retracted.pmid = 1;
+        retracted.is_retracted = true;
+        let plain = two_author_article();
+
+        let batch = to_record_batch(&[retracted, plain], false, false).unwrap();
+        let is_retracted = batch.column_by_name("is_retracted").unwrap().as_boolean();
+        assert!(is_retracted.value(0));
+        assert!(!is_retracted.value(1));
+    }
+
+    #[test]
+    fn is_ahead_of_print_column_reflects_the_article_field() {
+        let mut ahead = two_author_article();
+        ahead.pmid = 1;
+        ahead.is_ahead_of_print = true;
+        let mut ppublish = two_author_article();
+        ppublish.pmid = 2;
+
+        let batch = to_record_batch(&[ahead, ppublish], false, false).unwrap();
+        let is_ahead_of_print = batch.column_by_name("is_ahead_of_print").unwrap().as_boolean();
+        assert!(is_ahead_of_print.value(0));
+        assert!(!is_ahead_of_print.value(1));
+    }
+
+    #[test]
+    fn citation_subsets_and_journal_country_columns_reflect_the_article_fields() {
+        let mut with_subset = two_author_article();
+        with_subset.pmid = 1;
+        with_subset.citation_subsets = vec!["IM".to_string()];
+        with_subset.journal_country = Some("United States".to_string());
+        let plain = two_author_article();
+
+        let batch = to_record_batch(&[with_subset, plain], false, false).unwrap();
+
+        let citation_subsets = batch.column_by_name("citation_subsets").unwrap().as_list::<i32>();
+        assert_eq!(citation_subsets.value(0).as_string::<i32>().value(0), "IM");
+        assert!(citation_subsets.is_null(1));
+
+        let journal_country = batch.column_by_name("journal_country").unwrap().as_string::<i32>();
+        assert_eq!(journal_country.value(0), "United States");
+        assert!(journal_country.is_null(1));
+    }
+
+    #[test]
+    fn push_truncates_a_long_abstract_and_leaves_a_short_one_untouched() {
+        let mut accumulator = ArticleAccumulator::new(10, false, false, Some(10));
+
+        let mut long = two_author_article();
+        long.pmid = 1;
+        long.abstract_text = Some("0123456789abcdef".to_string());
+        accumulator.push(long, "source.xml.gz");
+
+        let mut short = two_author_article();
+        short.pmid = 2;
+        short.abstract_text = Some("short".to_string());
+        accumulator.push(short, "source.xml.gz");
+
+        assert_eq!(accumulator.truncated(), 1);
+
+        let batch = accumulator.take_batch().unwrap();
+        let abstracts = batch.column_by_name("abstract_text").unwrap().as_string::<i32>();
+        assert_eq!(abstracts.value(0), "0123456789…");
+        assert_eq!(abstracts.value(1), "short");
+    }
+}
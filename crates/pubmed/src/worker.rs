@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use flate2::read::GzDecoder;
+use papeline_core::sink::ParquetSink;
+use papeline_core::Result;
+
+use crate::config::Config;
+use crate::entries::{filter_by_range, filter_by_update_cutoff, Entry, EntryKind};
+use crate::model::Article;
+use crate::parse::parse_articles;
+use crate::transform::ArticleAccumulator;
+
+/// Summary of a single worker run, returned to the caller for logging.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WorkerStats {
+    pub files: usize,
+    pub articles: usize,
+    /// Number of `title`/`abstract_text` values truncated by
+    /// [`Config::max_text_len`], counted per field.
+    pub truncated: usize,
+}
+
+/// Per-file result passed to `run_with_callback`'s `on_file` hook.
+///
+/// `deleted` is always `0`: this parser doesn't read PubMed's
+/// `DeleteCitation` block. `parse_errors` is always `0` from
+/// `run_with_callback`, where a parse failure aborts the whole run rather
+/// than being recovered per-article (see [`crate::parse::parse_articles`]);
+/// [`run_validate_only`] sets it to `1` for a file that fails to parse.
+#[derive(Debug, Default, Clone)]
+pub struct FileResult {
+    pub filename: String,
+    pub articles: usize,
+    pub deleted: usize,
+    pub parse_errors: usize,
+}
+
+/// Parses every entry in `entries` (first narrowed by `config.file_range`
+/// and `config.update_cutoff`, when set), accumulating rows and flushing parquet
+/// shards as the batch fills. This is the shared loop behind both the
+/// baseline and updatefile passes; callers just point it at a different
+/// `Entry` list.
+pub fn run_with_entries(entries: &[Entry], config: &Config) -> Result<WorkerStats> {
+    run_with_callback(entries, config, |_| {})
+}
+
+/// Like [`run_with_entries`], but invokes `on_file` with a [`FileResult`]
+/// after each entry finishes parsing, for callers that want per-file
+/// visibility instead of waiting on the final `WorkerStats`.
+pub fn run_with_callback(
+    entries: &[Entry],
+    config: &Config,
+    on_file: impl FnMut(FileResult),
+) -> Result<WorkerStats> {
+    let mut entries = entries.to_vec();
+    if let Some(range) = config.file_range {
+        entries = filter_by_range(entries, range)?;
+    }
+    if let Some(cutoff) = config.update_cutoff {
+        entries = filter_by_update_cutoff(entries, cutoff)?;
+    }
+    if config.dedupe_pmids {
+        run_with_entries_deduped(&entries, config, on_file)
+    } else {
+        run_with_entries_streamed(&entries, config, on_file)
+    }
+}
+
+fn run_with_entries_streamed(
+    entries: &[Entry],
+    config: &Config,
+    mut on_file: impl FnMut(FileResult),
+) -> Result<WorkerStats> {
+    std::fs::create_dir_all(&config.output_dir)?;
+
+    let mut accumulator = ArticleAccumulator::new(
+        config.batch_rows,
+        config.record_provenance,
+        config.authors_as_struct,
+        config.max_text_len,
+    );
+    let mut stats = WorkerStats::default();
+    let mut shard = 0usize;
+
+    for entry in entries {
+        let filename = entry.filename();
+        let articles = parse_entry(entry)?;
+        let file_articles = articles.len();
+
+        for article in articles {
+            stats.articles += 1;
+            if accumulator.push(article, &filename) {
+                flush(&mut accumulator, config, &mut shard)?;
+            }
+        }
+        stats.files += 1;
+        on_file(FileResult {
+            filename,
+            articles: file_articles,
+            deleted: 0,
+            parse_errors: 0,
+        });
+    }
+
+    if !accumulator.is_empty() {
+        flush(&mut accumulator, config, &mut shard)?;
+    }
+    stats.truncated = accumulator.truncated();
+
+    Ok(stats)
+}
+
+/// Like [`run_with_entries_streamed`], but buffers every parsed article in a
+/// `pmid -> Article` map before writing any shard, keeping only the
+/// highest-`version` record per PMID (ties keep whichever entry came last in
+/// `entries`, except that with `config.prefer_updates` a tie between a
+/// baseline and an updatefile record keeps the updatefile's). This needs the full set of articles in memory up front, since
+/// a later entry can supersede a record already seen.
+///
+/// `on_file` fires per source file as it's parsed, before dedup collapses
+/// anything, so `articles` reflects what that file contributed rather than
+/// what survived into the output.
+fn run_with_entries_deduped(
+    entries: &[Entry],
+    config: &Config,
+    mut on_file: impl FnMut(FileResult),
+) -> Result<WorkerStats> {
+    std::fs::create_dir_all(&config.output_dir)?;
+
+    let mut seen: HashMap<u32, (Article, String, EntryKind)> = HashMap::new();
+    let mut stats = WorkerStats::default();
+
+    for entry in entries {
+        let filename = entry.filename();
+        let articles = parse_entry(entry)?;
+        let file_articles = articles.len();
+
+        for article in articles {
+            stats.articles += 1;
+            let keep = match seen.get(&article.pmid) {
+                Some((existing, _, existing_kind)) => match article.version.cmp(&existing.version) {
+                    std::cmp::Ordering::Greater => true,
+                    std::cmp::Ordering::Less => false,
+                    std::cmp::Ordering::Equal => {
+                        if config.prefer_updates && entry.kind != *existing_kind {
+                            entry.kind == EntryKind::Updatefile
+                        } else {
+                            true
+                        }
+                    }
+                },
+                None => true,
+            };
+            if keep {
+                seen.insert(article.pmid, (article, filename.clone(), entry.kind));
+            }
+        }
+        stats.files += 1;
+        on_file(FileResult {
+            filename,
+            articles: file_articles,
+            deleted: 0,
+            parse_errors: 0,
+        });
+    }
+
+    let mut accumulator = ArticleAccumulator::new(
+        config.batch_rows,
+        config.record_provenance,
+        config.authors_as_struct,
+        config.max_text_len,
+    );
+    let mut shard = 0usize;
+    let mut deduped: Vec<(u32, (Article, String, EntryKind))> = seen.into_iter().collect();
+    deduped.sort_by_key(|(pmid, _)| *pmid);
+
+    for (_, (article, filename, _)) in deduped {
+        if accumulator.push(article, &filename) {
+            flush(&mut accumulator, config, &mut shard)?;
+        }
+    }
+    if !accumulator.is_empty() {
+        flush(&mut accumulator, config, &mut shard)?;
+    }
+    stats.truncated = accumulator.truncated();
+
+    Ok(stats)
+}
+
+/// Tally produced by [`run_validate_only`]: how many articles/deletions/
+/// parse-errors each entry contained, without ever writing parquet.
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    pub files: Vec<FileResult>,
+    pub total_articles: usize,
+    pub total_deleted: usize,
+    pub total_parse_errors: usize,
+}
+
+/// Parses every entry in `entries` and tallies per-file counts, skipping
+/// the accumulator/[`ParquetSink`] path entirely. Meant for confirming a
+/// new baseline or updatefile parses cleanly before committing to a full
+/// transform/write, e.g. right after a fresh drop lands and before running
+/// [`run_with_entries`] against it for real.
+///
+/// A parse failure on one entry is recorded as a `parse_errors` count on
+/// that entry's [`FileResult`] instead of aborting the whole run, unlike
+/// [`run_with_entries`], since the point of validation is to find out how
+/// many files are bad, not to stop at the first one.
+pub fn run_validate_only(entries: &[Entry]) -> Result<ValidationReport> {
+    let mut report = ValidationReport::default();
+
+    for entry in entries {
+        let filename = entry.filename();
+        let result = match parse_entry(entry) {
+            Ok(articles) => FileResult {
+                filename,
+                articles: articles.len(),
+                deleted: 0,
+                parse_errors: 0,
+            },
+            Err(_) => FileResult {
+                filename,
+                articles: 0,
+                deleted: 0,
+                parse_errors: 1,
+            },
+        };
+        report.total_articles += result.articles;
+        report.total_deleted += result.deleted;
+        report.total_parse_errors += result.parse_errors;
+        report.files.push(result);
+    }
+
+    Ok(report)
+}
+
+fn parse_entry(entry: &Entry) -> Result<Vec<Article>> {
+    let file = File::open(&entry.path)?;
+    let reader = BufReader::new(GzDecoder::new(file));
+    parse_articles(reader).map_err(|e| papeline_core::Error::Other(e.to_string()))
+}
+
+fn flush(accumulator: &mut ArticleAccumulator, config: &Config, shard: &mut usize) -> Result<()> {
+    let batch = accumulator.take_batch()?;
+    let path = config.output_dir.join(format!("part-{shard:05}.parquet"));
+    let mut sink = ParquetSink::create(&path, accumulator.schema(), config.zstd_level)?;
+    if let Err(e) = sink.write_batch(&batch) {
+        sink.abort();
+        return Err(e);
+    }
+    sink.finalize()?;
+    *shard += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use arrow::array::{Array, StringArray};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use crate::entries::EntryKind;
+
+    use super::*;
+
+    fn write_gz_article(path: &std::path::Path, pmid: u32, title: &str) {
+        write_gz_article_versioned(path, pmid, 1, title);
+    }
+
+    fn write_gz_article_versioned(path: &std::path::Path, pmid: u32, version: u32, title: &str) {
+        let xml = format!(
+            "<PubmedArticleSet><PubmedArticle><MedlineCitation>\
+             <PMID Version=\"{version}\">{pmid}</PMID>\
+             <Article><ArticleTitle>{title}</ArticleTitle></Article>\
+             </MedlineCitation></PubmedArticle></PubmedArticleSet>"
+        );
+        let file = File::create(path).unwrap();
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+    }
+
+    #[test]
+    fn source_file_tracks_originating_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_a = dir.path().join("baseline-a.xml.gz");
+        let file_b = dir.path().join("baseline-b.xml.gz");
+        write_gz_article(&file_a, 1, "Title A");
+        write_gz_article(&file_b, 2, "Title B");
+
+        let out_dir = dir.path().join("out");
+        let mut config = Config::new(dir.path().into(), dir.path().into(), out_dir.clone());
+        config.record_provenance = true;
+
+        let entries = vec![
+            Entry {
+                path: file_a,
+                kind: EntryKind::Baseline,
+            },
+            Entry {
+                path: file_b,
+                kind: EntryKind::Baseline,
+            },
+        ];
+
+        let stats = run_with_entries(&entries, &config).unwrap();
+        assert_eq!(stats.articles, 2);
+
+        let part = out_dir.join("part-00000.parquet");
+        let file = File::open(part).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut source_files = Vec::new();
+        for batch in reader {
+            let batch = batch.unwrap();
+            let col = batch
+                .column_by_name("source_file")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            for i in 0..col.len() {
+                source_files.push(col.value(i).to_string());
+            }
+        }
+
+        assert_eq!(
+            source_files,
+            vec!["baseline-a.xml.gz".to_string(), "baseline-b.xml.gz".to_string()]
+        );
+    }
+
+    #[test]
+    fn dedupe_pmids_keeps_only_the_highest_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = dir.path().join("baseline.xml.gz");
+        let update = dir.path().join("update.xml.gz");
+        write_gz_article_versioned(&baseline, 1, 1, "Old Title");
+        write_gz_article_versioned(&update, 1, 2, "New Title");
+
+        let out_dir = dir.path().join("out");
+        let mut config = Config::new(dir.path().into(), dir.path().into(), out_dir.clone());
+        config.dedupe_pmids = true;
+
+        let entries = vec![
+            Entry {
+                path: baseline,
+                kind: EntryKind::Baseline,
+            },
+            Entry {
+                path: update,
+                kind: EntryKind::Updatefile,
+            },
+        ];
+
+        let stats = run_with_entries(&entries, &config).unwrap();
+        assert_eq!(stats.articles, 2, "both versions are parsed before dedup collapses them");
+
+        let part = out_dir.join("part-00000.parquet");
+        let (_, rows) = papeline_core::testutil::read_parquet_rows(&part).unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(papeline_core::testutil::column_values(&part, "title").unwrap(), vec!["New Title"]);
+    }
+
+    #[test]
+    fn prefer_updates_wins_a_version_tie_regardless_of_processing_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = dir.path().join("baseline.xml.gz");
+        let update = dir.path().join("update.xml.gz");
+        write_gz_article_versioned(&baseline, 1, 1, "Baseline Title");
+        write_gz_article_versioned(&update, 1, 1, "Update Title");
+
+        let out_dir = dir.path().join("out");
+        let mut config = Config::new(dir.path().into(), dir.path().into(), out_dir.clone());
+        config.dedupe_pmids = true;
+        config.prefer_updates = true;
+
+        // The updatefile is processed before the baseline, which would make
+        // the baseline win under plain "last processed wins" tie-breaking.
+        let entries = vec![
+            Entry {
+                path: update,
+                kind: EntryKind::Updatefile,
+            },
+            Entry {
+                path: baseline,
+                kind: EntryKind::Baseline,
+            },
+        ];
+
+        run_with_entries(&entries, &config).unwrap();
+
+        let part = out_dir.join("part-00000.parquet");
+        assert_eq!(
+            papeline_core::testutil::column_values(&part, "title").unwrap(),
+            vec!["Update Title"]
+        );
+    }
+
+    #[test]
+    fn run_validate_only_tallies_counts_and_writes_no_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_a = dir.path().join("baseline-a.xml.gz");
+        let file_b = dir.path().join("baseline-b.xml.gz");
+        write_gz_article(&file_a, 1, "Title A");
+        write_gz_article(&file_b, 2, "Title B");
+
+        let entries = vec![
+            Entry {
+                path: file_a,
+                kind: EntryKind::Baseline,
+            },
+            Entry {
+                path: file_b,
+                kind: EntryKind::Baseline,
+            },
+        ];
+
+        let report = run_validate_only(&entries).unwrap();
+
+        assert_eq!(report.total_articles, 2);
+        assert_eq!(report.total_parse_errors, 0);
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].filename, "baseline-a.xml.gz");
+        assert_eq!(report.files[0].articles, 1);
+
+        assert!(!dir.path().join("out").exists(), "validation must not create an output dir or write any parquet");
+    }
+
+    #[test]
+    fn run_with_callback_fires_once_per_file_with_correct_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_a = dir.path().join("baseline-a.xml.gz");
+        let file_b = dir.path().join("baseline-b.xml.gz");
+        write_gz_article(&file_a, 1, "Title A");
+        write_gz_article(&file_b, 2, "Title B");
+
+        let out_dir = dir.path().join("out");
+        let config = Config::new(dir.path().into(), dir.path().into(), out_dir);
+
+        let entries = vec![
+            Entry {
+                path: file_a,
+                kind: EntryKind::Baseline,
+            },
+            Entry {
+                path: file_b,
+                kind: EntryKind::Baseline,
+            },
+        ];
+
+        let mut results = Vec::new();
+        run_with_callback(&entries, &config, |result| results.push(result)).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].filename, "baseline-a.xml.gz");
+        assert_eq!(results[0].articles, 1);
+        assert_eq!(results[1].filename, "baseline-b.xml.gz");
+        assert_eq!(results[1].articles, 1);
+    }
+}